@@ -1,14 +1,51 @@
-use winnow::combinator::trace;
-use winnow::error::ParserError;
-use winnow::stream::{AsChar, Stream, StreamIsPartial};
-use winnow::token::take_till;
-use winnow::{Parser, Result};
+use winnow::combinator::{alt, cut_err, fail, peek, repeat, trace};
+use winnow::error::{ParserError, StrContext};
+use winnow::stream::{AsChar, Compare, Stream, StreamIsPartial};
+use winnow::token::{any, none_of, take_till};
+use winnow::{dispatch, ModalResult, Parser};
 
 #[inline]
-pub fn not_whitespace<I, E: ParserError<I>>(input: &mut I) -> Result<<I as Stream>::Slice, E>
+pub fn not_whitespace<I, E: ParserError<I>>(input: &mut I) -> ModalResult<<I as Stream>::Slice, E>
 where
     I: StreamIsPartial + Stream,
     <I as Stream>::Token: AsChar,
 {
     trace("not_whitespace", take_till(1.., AsChar::is_space)).parse_next(input)
 }
+
+/// An interpreted (`"..."`) or raw (`` `...` ``) Go string literal on a
+/// single line, including its quotes. Escape sequences are not validated.
+pub fn string_literal<I>(input: &mut I) -> ModalResult<<I as Stream>::Slice>
+where
+    I: StreamIsPartial + Stream + Compare<char>,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let literal = dispatch! {peek(any).map(AsChar::as_char);
+        '"' => (
+            '"',
+            cut_err((
+                repeat::<_, _, (), _, _>(
+                    0..,
+                    alt((
+                        ('\\', none_of(['\r', '\n'])).void(),
+                        none_of(['"', '\\', '\r', '\n']).void(),
+                    )),
+                ),
+                '"',
+            ))
+            .context(StrContext::Expected('"'.into())),
+        )
+            .void(),
+        '`' => (
+            '`',
+            cut_err((take_till(0.., ['`', '\r', '\n']), '`'))
+                .context(StrContext::Expected('`'.into())),
+        )
+            .void(),
+        _ => fail,
+    };
+
+    trace("string_literal", literal.take())
+        .context(StrContext::Label("string"))
+        .parse_next(input)
+}
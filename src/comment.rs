@@ -0,0 +1,304 @@
+//! Attachment of comments to directives and entries, following the rules of
+//! Go's `modfile` package: the comment lines directly above a line and the
+//! comment ending it belong to it, while comments followed by a blank line
+//! stand alone.
+
+use crate::quote::token_value;
+use crate::syntax::{FileSyntax, Line, Stmt};
+use crate::{Comments, GoMod, Replacement, VersionInterval};
+
+/// A top-level statement with its comments.
+enum Item {
+    /// Comment lines not attached to any line.
+    Comments(Vec<String>),
+    Line(Entry),
+    Block {
+        verb: String,
+        comments: Comments,
+        entries: Vec<Entry>,
+    },
+}
+
+/// A directive line or block entry.
+struct Entry {
+    /// The unquoted tokens, including the verb of top-level lines.
+    args: Vec<String>,
+    comments: Comments,
+}
+
+/// The directives whose entries carry their comments.
+const ENTRY_VERBS: [&str; 6] = ["require", "exclude", "replace", "retract", "tool", "ignore"];
+
+impl Comments {
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        self.before.iter().chain(&self.suffix)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.before.is_empty() && self.suffix.is_none()
+    }
+}
+
+impl GoMod {
+    /// Attaches the comments of the syntax tree to the entries they belong
+    /// to, fills [`GoMod::deprecated`] and the retract rationales, and
+    /// collects the remaining ones in [`GoMod::comment`].
+    pub(crate) fn attach_comments(&mut self) {
+        if self.syntax.stmts.is_empty() {
+            return;
+        }
+
+        let entries = self.collect_comments();
+        self.attach_entry_comments(&entries);
+    }
+
+    /// Sets the comments not attached to entries and returns the entries of
+    /// `ENTRY_VERBS` directives, by verb.
+    fn collect_comments(&mut self) -> Vec<(String, Entry)> {
+        let mut comment = Vec::new();
+        let mut entries = Vec::new();
+        for item in items(&self.syntax) {
+            match item {
+                Item::Comments(lines) => comment.extend(lines),
+                Item::Line(mut entry) => {
+                    let verb = entry.args.remove(0);
+                    match verb.as_str() {
+                        v if ENTRY_VERBS.contains(&v) => entries.push((verb, entry)),
+                        "module" => {
+                            let lines: Vec<_> = entry.comments.lines().cloned().collect();
+                            self.deprecated = deprecation(&lines.join("\n"));
+                            if self.deprecated.is_some() {
+                                comment.extend(without_deprecation(&lines));
+                            } else {
+                                comment.extend(lines);
+                            }
+                        }
+                        _ => comment.extend(entry.comments.lines().cloned()),
+                    }
+                }
+                Item::Block {
+                    verb,
+                    comments,
+                    entries: block,
+                } => match verb.as_str() {
+                    // the entries of a retract block fall back to its comments
+                    "retract" => entries.extend(block.into_iter().map(|mut entry| {
+                        if entry.comments.is_empty() {
+                            entry.comments = comments.clone();
+                        }
+                        (verb.clone(), entry)
+                    })),
+                    v if ENTRY_VERBS.contains(&v) => {
+                        comment.extend(comments.lines().cloned());
+                        entries.extend(block.into_iter().map(|entry| (verb.clone(), entry)));
+                    }
+                    _ => {
+                        comment.extend(comments.lines().cloned());
+                        for entry in block {
+                            comment.extend(entry.comments.lines().cloned());
+                        }
+                    }
+                },
+            }
+        }
+        self.comment = comment;
+
+        entries
+    }
+
+    fn attach_entry_comments(&mut self, entries: &[(String, Entry)]) {
+        attach(
+            entries,
+            "require",
+            &mut self.require,
+            |dependency| vec![&dependency.module.module_path, &dependency.module.version],
+            |dependency, comments| {
+                dependency.comments = comments.clone();
+                // the `// indirect` marker is kept in `indirect`, its note in
+                // the suffix
+                if dependency.indirect {
+                    let note = comments.suffix.as_deref().and_then(indirect_note);
+                    dependency.comments.suffix =
+                        note.filter(|note| !note.is_empty()).map(String::from);
+                }
+            },
+        );
+        attach(
+            entries,
+            "exclude",
+            &mut self.exclude,
+            |dependency| vec![&dependency.module.module_path, &dependency.module.version],
+            |dependency, comments| dependency.comments = comments.clone(),
+        );
+        attach(
+            entries,
+            "replace",
+            &mut self.replace,
+            |replacement| {
+                let mut args = vec![replacement.module_path.as_str()];
+                args.extend(replacement.version.as_deref());
+                args.push("=>");
+                match &replacement.replacement {
+                    Replacement::FilePath(path) => args.push(path),
+                    Replacement::Module(module) => {
+                        args.extend([&*module.module_path, &*module.version]);
+                    }
+                }
+                args
+            },
+            |replacement, comments| replacement.comments = comments.clone(),
+        );
+        attach(
+            entries,
+            "retract",
+            &mut self.retract,
+            |retract| match &retract.interval {
+                VersionInterval::Single(version) => vec![version.as_str()],
+                VersionInterval::Range(low, high) => vec!["[", low, ",", high, "]"],
+            },
+            |retract, comments| {
+                retract.rationale = Some(join(comments.lines())).filter(|r| !r.is_empty());
+            },
+        );
+        for (verb, values) in [("tool", &mut self.tool), ("ignore", &mut self.ignore)] {
+            attach(
+                entries,
+                verb,
+                values,
+                |entry| vec![&entry.path],
+                |entry, comments| entry.comments = comments.clone(),
+            );
+        }
+    }
+}
+
+/// Attaches the comments of the `verb` entries to the `values` with the same
+/// arguments. They are matched in order, as invalid entries are skipped by
+/// the parser.
+fn attach<T>(
+    entries: &[(String, Entry)],
+    verb: &str,
+    values: &mut [T],
+    args: impl Fn(&T) -> Vec<&str>,
+    set: impl Fn(&mut T, &Comments),
+) {
+    let mut entries = entries
+        .iter()
+        .filter(|(v, _)| v == verb)
+        .map(|(_, entry)| entry);
+    for value in values {
+        let args = args(value);
+        if let Some(entry) = entries.find(|entry| entry.args == args) {
+            set(value, &entry.comments);
+        }
+    }
+}
+
+fn items(syntax: &FileSyntax) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut before = Vec::new();
+
+    for stmt in &syntax.stmts {
+        match stmt {
+            Stmt::Line(line) if line.is_blank() => {
+                if !before.is_empty() {
+                    items.push(Item::Comments(std::mem::take(&mut before)));
+                }
+            }
+            Stmt::Line(line) if line.is_comment() => before.extend(comment_text(line)),
+            Stmt::Line(line) => items.push(Item::Line(Entry {
+                args: line.values().map(token_value).collect(),
+                comments: Comments {
+                    before: std::mem::take(&mut before),
+                    suffix: comment_text(line),
+                },
+            })),
+            Stmt::Block(block) => {
+                let mut entries = Vec::new();
+                let mut entry_before = Vec::new();
+                for line in &block.lines {
+                    if line.is_blank() {
+                        entry_before.clear();
+                    } else if line.is_comment() {
+                        entry_before.extend(comment_text(line));
+                    } else {
+                        entries.push(Entry {
+                            args: line.values().map(token_value).collect(),
+                            comments: Comments {
+                                before: std::mem::take(&mut entry_before),
+                                suffix: comment_text(line),
+                            },
+                        });
+                    }
+                }
+
+                items.push(Item::Block {
+                    verb: block.verb().unwrap_or_default().to_string(),
+                    comments: Comments {
+                        before: std::mem::take(&mut before),
+                        suffix: comment_text(&block.open),
+                    },
+                    entries,
+                });
+            }
+        }
+    }
+    if !before.is_empty() {
+        items.push(Item::Comments(before));
+    }
+
+    items
+}
+
+fn comment_text(line: &Line) -> Option<String> {
+    line.comment
+        .as_ref()
+        .map(|comment| comment.text.trim_start_matches("//").trim().to_string())
+}
+
+fn join<'a>(lines: impl Iterator<Item = &'a String>) -> String {
+    lines.map(String::as_str).collect::<Vec<_>>().join("\n")
+}
+
+/// The note of a `// indirect` marker, the text following `indirect;`, or an
+/// empty one for a plain marker. Returns `None` if `comment` is no marker,
+/// following Go's `isIndirect`.
+pub fn indirect_note(comment: &str) -> Option<&str> {
+    let text = comment.trim_start_matches("//").trim();
+    if text == "indirect" {
+        return Some("");
+    }
+
+    let note = text.strip_prefix("indirect;")?;
+    (note.starts_with(char::is_whitespace)).then(|| note.trim_start())
+}
+
+/// The text of the first paragraph starting with `Deprecated:`.
+pub fn deprecation(comment: &str) -> Option<String> {
+    comment
+        .split("\n\n")
+        .find_map(|paragraph| paragraph.strip_prefix("Deprecated:"))
+        .map(|message| message.trim_start_matches(' ').to_string())
+        .filter(|message| !message.is_empty())
+}
+
+/// The comment lines without the first paragraph starting with `Deprecated:`,
+/// which is rendered from [`GoMod::deprecated`].
+fn without_deprecation(lines: &[String]) -> Vec<String> {
+    let start = (0..lines.len())
+        .find(|&i| (i == 0 || lines[i - 1].is_empty()) && lines[i].starts_with("Deprecated:"));
+    let Some(start) = start else {
+        return lines.to_vec();
+    };
+    let end = (start..lines.len())
+        .find(|&i| lines[i].is_empty())
+        .unwrap_or(lines.len());
+
+    // along with the blank line separating it from the preceding paragraph
+    let start = start.saturating_sub(1);
+    lines[..start]
+        .iter()
+        .chain(&lines[end..])
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,269 @@
+//! Format-preserving editing of a parsed `go.mod` file.
+//!
+//! Every method updates the fields of a [`GoMod`] together with its
+//! [`syntax`](GoMod::syntax) tree, so rendering the tree afterwards keeps the
+//! comments, blocks and ordering of the original file.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gomod_parser::GoMod;
+//! use std::str::FromStr;
+//!
+//! let input = r#"module github.com/example
+//!
+//! require (
+//!     // pinned for compatibility
+//!     golang.org/x/net v0.20.0
+//! )
+//! "#;
+//!
+//! let mut go_mod = GoMod::from_str(input).unwrap();
+//! go_mod.set_require_version("golang.org/x/net", "v0.21.0");
+//! go_mod.add_require("golang.org/x/text", "v0.14.0");
+//!
+//! assert_eq!(
+//!     go_mod.syntax.to_string(),
+//!     r#"module github.com/example
+//!
+//! require (
+//!     // pinned for compatibility
+//!     golang.org/x/net v0.21.0
+//!     golang.org/x/text v0.14.0
+//! )
+//! "#
+//! );
+//! ```
+
+use crate::quote::auto_quote;
+use crate::syntax::Line;
+use crate::{Comments, GoMod, Module, ModuleDependency, ModuleReplacement, Replacement};
+
+impl GoMod {
+    /// Requires `module_path` at `version`, updating the version if it is
+    /// already required.
+    pub fn add_require(&mut self, module_path: &str, version: &str) {
+        if self.set_require_version(module_path, version) {
+            return;
+        }
+
+        self.require.push(ModuleDependency {
+            module: Module {
+                module_path: module_path.to_string(),
+                version: version.to_string(),
+            },
+            indirect: false,
+            comments: Comments::default(),
+        });
+        self.add_entry(tokens(&["require", module_path, version]));
+    }
+
+    /// Removes all requirements of `module_path`. Returns `true`, if any was
+    /// removed.
+    pub fn drop_require(&mut self, module_path: &str) -> bool {
+        let len = self.require.len();
+        self.require
+            .retain(|dependency| dependency.module.module_path != module_path);
+        self.syntax
+            .remove_entries("require", |args| is_module(args, module_path));
+
+        self.require.len() != len
+    }
+
+    /// Sets the version of all requirements of `module_path`. Returns `false`,
+    /// if `module_path` is not required.
+    pub fn set_require_version(&mut self, module_path: &str, version: &str) -> bool {
+        let mut found = false;
+        for dependency in &mut self.require {
+            if dependency.module.module_path == module_path {
+                dependency.module.version = version.to_string();
+                found = true;
+            }
+        }
+
+        if found {
+            self.syntax.update_entries("require", |args| {
+                is_module(args, module_path).then(|| tokens(&[module_path, version]))
+            });
+        }
+
+        found
+    }
+
+    /// Excludes `module_path` at `version`, unless it is already excluded.
+    pub fn add_exclude(&mut self, module_path: &str, version: &str) {
+        let is_excluded = self.exclude.iter().any(|dependency| {
+            dependency.module.module_path == module_path && dependency.module.version == version
+        });
+        if is_excluded {
+            return;
+        }
+
+        self.exclude.push(ModuleDependency {
+            module: Module {
+                module_path: module_path.to_string(),
+                version: version.to_string(),
+            },
+            indirect: false,
+            comments: Comments::default(),
+        });
+        self.add_entry(tokens(&["exclude", module_path, version]));
+    }
+
+    /// Removes the exclusion of `module_path` at `version`. Returns `true`, if
+    /// it was excluded.
+    pub fn drop_exclude(&mut self, module_path: &str, version: &str) -> bool {
+        let len = self.exclude.len();
+        self.exclude.retain(|dependency| {
+            dependency.module.module_path != module_path || dependency.module.version != version
+        });
+        self.syntax.remove_entries("exclude", |args| {
+            is_module(args, module_path) && args.get(1).map(String::as_str) == Some(version)
+        });
+
+        self.exclude.len() != len
+    }
+
+    /// Replaces `module_path`, or only its `version` if given, by
+    /// `replacement`.
+    ///
+    /// Like `go mod edit -replace`, an existing replacement of the same module
+    /// and version is updated in place. Without `version`, this also
+    /// supersedes the version-specific replacements of `module_path`.
+    pub fn add_replace(
+        &mut self,
+        module_path: &str,
+        version: Option<&str>,
+        replacement: Replacement,
+    ) {
+        let matches = |path: &str, v: Option<&str>| {
+            path == module_path && (version.is_none() || v == version)
+        };
+
+        let mut args = vec![module_path];
+        args.extend(version);
+        args.push("=>");
+        match &replacement {
+            Replacement::FilePath(path) => args.push(path),
+            Replacement::Module(module) => args.extend([&*module.module_path, &*module.version]),
+        }
+        let args = tokens(&args);
+
+        let new = ModuleReplacement {
+            module_path: module_path.to_string(),
+            version: version.map(ToString::to_string),
+            replacement,
+            comments: Comments::default(),
+        };
+
+        let Some(first) = self
+            .replace
+            .iter()
+            .position(|r| matches(&r.module_path, r.version.as_deref()))
+        else {
+            self.replace.push(new);
+            self.add_entry([vec!["replace".to_string()], args].concat());
+            return;
+        };
+
+        // the updated line keeps its comments
+        let comments = std::mem::take(&mut self.replace[first].comments);
+        self.replace[first] = ModuleReplacement { comments, ..new };
+        let mut i = first + 1;
+        while i < self.replace.len() {
+            if matches(
+                &self.replace[i].module_path,
+                self.replace[i].version.as_deref(),
+            ) {
+                self.replace.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        let mut updated = false;
+        self.syntax.update_entries("replace", |old| {
+            let (path, v) = replaced_module(old)?;
+            (!updated && matches(path, v)).then(|| {
+                updated = true;
+                args.clone()
+            })
+        });
+        let mut kept = false;
+        self.syntax.remove_entries("replace", |old| {
+            replaced_module(old).map_or(false, |(path, v)| {
+                let remove = kept && matches(path, v);
+                kept |= matches(path, v);
+                remove
+            })
+        });
+    }
+
+    /// Removes the replacement of `module_path` at `version`, `None` matching
+    /// only replacements of all versions. Returns `true`, if it was replaced.
+    pub fn drop_replace(&mut self, module_path: &str, version: Option<&str>) -> bool {
+        let len = self.replace.len();
+        self.replace
+            .retain(|r| r.module_path != module_path || r.version.as_deref() != version);
+        self.syntax.remove_entries("replace", |args| {
+            replaced_module(args) == Some((module_path, version))
+        });
+
+        self.replace.len() != len
+    }
+
+    /// Sets the `go` version, adding the directive after `module` if missing.
+    pub fn set_go(&mut self, version: &str) {
+        self.go = Some(version.to_string());
+        self.set_single("go", version, &["module"]);
+    }
+
+    /// Sets the `toolchain`, adding the directive after `go` if missing.
+    pub fn set_toolchain(&mut self, name: &str) {
+        self.toolchain = Some(name.to_string());
+        self.set_single("toolchain", name, &["go", "module"]);
+    }
+
+    /// Adds an entry to the syntax tree, like `modfile`'s `addLine`.
+    fn add_entry(&mut self, tokens: Vec<String>) {
+        let is_parsed = !self.syntax.stmts.is_empty();
+        self.syntax.add_entry(tokens);
+
+        // converting a single line into a block moves its comments to the
+        // block, so they are attached anew
+        if is_parsed {
+            self.attach_comments();
+        }
+    }
+
+    /// Updates the single-valued `verb` directive, or adds it after the first
+    /// existing directive of `after`.
+    fn set_single(&mut self, verb: &str, value: &str, after: &[&str]) {
+        if self.syntax.update_entries(verb, |_| Some(tokens(&[value]))) {
+            return;
+        }
+
+        match after.iter().find_map(|verb| self.syntax.position(verb)) {
+            Some(i) => self.syntax.insert_line_after(i, Line::new([verb, value])),
+            None => self.syntax.add_entry(tokens(&[verb, value])),
+        }
+    }
+}
+
+/// The tokens of `values`, quoted if needed.
+fn tokens(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| auto_quote(value)).collect()
+}
+
+fn is_module(args: &[String], module_path: &str) -> bool {
+    args.first().map(String::as_str) == Some(module_path)
+}
+
+/// The module path and optional version left of `=>` in `replace` arguments.
+fn replaced_module(args: &[String]) -> Option<(&str, Option<&str>)> {
+    match args.iter().position(|arg| arg == "=>")? {
+        1 => Some((&args[0], None)),
+        2 => Some((&args[0], Some(&args[1]))),
+        _ => None,
+    }
+}
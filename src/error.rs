@@ -0,0 +1,98 @@
+use crate::parser::Input;
+use std::fmt;
+use winnow::error::{ContextError, StrContext};
+
+/// An error describing where and why parsing a `go.mod` or `go.sum` file
+/// failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the input at which the error occurred.
+    pub offset: usize,
+    /// 1-based line number of the error.
+    pub line: usize,
+    /// 1-based column of the error, counted in characters.
+    pub column: usize,
+    /// The directive being parsed when the error occurred, e.g. `require block`,
+    /// or `checksum` for `go.sum` lines.
+    pub directive: Option<String>,
+    /// What the parser expected to find at the error position.
+    pub expected: Vec<String>,
+    /// The token found at the error position, `None` at the end of input.
+    pub found: Option<String>,
+}
+
+impl ParseError {
+    pub(crate) fn new(input: &str, offset: usize, error: &ContextError) -> Self {
+        let (line, column) = position(input, offset);
+
+        let directive = error.context().find_map(|c| match c {
+            StrContext::Label(label) => Some((*label).to_string()),
+            _ => None,
+        });
+        let expected = error
+            .context()
+            .filter_map(|c| match c {
+                StrContext::Expected(value) => Some(value.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            offset,
+            line,
+            column,
+            directive,
+            expected,
+            found: found(&input[offset..]),
+        }
+    }
+}
+
+impl From<winnow::error::ParseError<Input<'_>, ContextError>> for ParseError {
+    fn from(error: winnow::error::ParseError<Input<'_>, ContextError>) -> Self {
+        Self::new(error.input().input, error.offset(), error.inner())
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+
+        if let Some(directive) = &self.directive {
+            write!(f, "invalid {directive}, ")?;
+        }
+        if !self.expected.is_empty() {
+            write!(f, "expected {}, ", self.expected.join(" or "))?;
+        }
+
+        match &self.found {
+            Some(found) => write!(f, "found `{}`", found.escape_debug()),
+            None => write!(f, "found end of input"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Translates a byte offset into a 1-based line and column.
+fn position(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Returns the token starting at the error position, or the offending
+/// whitespace character if there is none.
+fn found(rest: &str) -> Option<String> {
+    let token = rest.split(char::is_whitespace).next().unwrap_or_default();
+
+    if token.is_empty() {
+        rest.chars().next().map(String::from)
+    } else {
+        Some(token.to_string())
+    }
+}
@@ -0,0 +1,663 @@
+//! Canonical formatting of `go.mod` files, equivalent to `go mod edit -fmt`.
+//!
+//! The layout follows the Go toolchain's `modfile` printer:
+//!
+//! - top-level directives, blocks and comment groups are separated by exactly
+//!   one blank line, comments directly above a directive stay attached to it
+//! - tokens are separated by single spaces and same-line comments by one space
+//! - block entries are indented by a tab, blank lines within blocks are
+//!   collapsed and removed at their start and end
+//! - blocks with a single entry are collapsed into a single line, blocks
+//!   without entries are removed, except for those of unknown directives,
+//!   whose shape is kept
+//! - `// indirect` markers are normalized
+//!
+//! Like `go mod edit -fmt`, [`format()`] also sorts the entries of blocks and
+//! removes duplicate `exclude`, `replace`, `tool` and `ignore` entries, with
+//! the comments above them. Entries are sorted by their tokens, `exclude`
+//! entries by semantic version from `go 1.21` on and `retract` entries by
+//! descending version.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gomod_parser::format::format;
+//! use gomod_parser::syntax::FileSyntax;
+//! use std::str::FromStr;
+//!
+//! let input = "module   github.com/example\ngo 1.21\nrequire (\n    golang.org/x/net v0.20.0 //indirect\n)\n";
+//!
+//! let syntax = FileSyntax::from_str(input).unwrap();
+//!
+//! assert_eq!(
+//!     format(&syntax),
+//!     "module github.com/example\n\ngo 1.21\n\nrequire golang.org/x/net v0.20.0 // indirect\n"
+//! );
+//! ```
+//!
+//! The model types implement [`Display`](fmt::Display) in the same layout, so
+//! a [`GoMod`] built in code renders to a valid `go.mod` file:
+//!
+//! ```rust
+//! use gomod_parser::{Comments, GoMod, Module, ModuleDependency};
+//!
+//! let go_mod = GoMod {
+//!     module: "github.com/example".to_string(),
+//!     go: Some("1.21".to_string()),
+//!     require: vec![ModuleDependency {
+//!         module: Module {
+//!             module_path: "golang.org/x/net".to_string(),
+//!             version: "v0.20.0".to_string(),
+//!         },
+//!         indirect: true,
+//!         comments: Comments::default(),
+//!     }],
+//!     ..Default::default()
+//! };
+//!
+//! assert_eq!(
+//!     go_mod.to_string(),
+//!     "module github.com/example\n\ngo 1.21\n\nrequire golang.org/x/net v0.20.0 // indirect\n"
+//! );
+//! ```
+//!
+//! Values which cannot be written as plain tokens, like paths with spaces,
+//! are quoted.
+
+use crate::comment::{deprecation, indirect_note};
+use crate::parser::DIRECTIVES;
+use crate::quote::{auto_quote, token_value};
+use crate::syntax::{FileSyntax, Line, LineBlock, Stmt, Token};
+use crate::{
+    Comments, GoMod, Module, ModuleDependency, ModuleReplacement, ModuleRetract, PathEntry,
+    Replacement, UnknownDirective, Version, VersionInterval,
+};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Formats `syntax` canonically.
+#[must_use]
+pub fn format(syntax: &FileSyntax) -> String {
+    canonical(&cleanup(syntax)).to_string()
+}
+
+impl GoMod {
+    /// Formats the parsed file canonically, see [`format()`].
+    #[must_use]
+    pub fn format(&self) -> String {
+        format(&self.syntax)
+    }
+}
+
+/// Returns `syntax` in canonical layout.
+pub(crate) fn canonical(syntax: &FileSyntax) -> FileSyntax {
+    let mut groups: Vec<Vec<Stmt>> = Vec::new();
+    let mut comments: Vec<Line> = Vec::new();
+
+    for stmt in &syntax.stmts {
+        match stmt {
+            Stmt::Line(line) if line.is_blank() => {
+                if !comments.is_empty() {
+                    groups.push(
+                        std::mem::take(&mut comments)
+                            .into_iter()
+                            .map(Stmt::Line)
+                            .collect(),
+                    );
+                }
+            }
+            Stmt::Line(line) if line.is_comment() => comments.push(line.clone()),
+            Stmt::Line(line) => {
+                comments.push(line.clone());
+                groups.push(
+                    std::mem::take(&mut comments)
+                        .into_iter()
+                        .map(Stmt::Line)
+                        .collect(),
+                );
+            }
+            Stmt::Block(block) => {
+                let stmts = block_stmts(block);
+                // like `modfile`, drop empty blocks along with their comments
+                if stmts.is_empty() {
+                    comments.clear();
+                    continue;
+                }
+                let mut group: Vec<Stmt> = std::mem::take(&mut comments)
+                    .into_iter()
+                    .map(Stmt::Line)
+                    .collect();
+                group.extend(stmts);
+                groups.push(group);
+            }
+        }
+    }
+    if !comments.is_empty() {
+        groups.push(comments.into_iter().map(Stmt::Line).collect());
+    }
+
+    let mut stmts = Vec::new();
+    for (i, group) in groups.into_iter().enumerate() {
+        if i > 0 {
+            stmts.push(Stmt::Line(Line::new(Vec::<String>::new())));
+        }
+        stmts.extend(group.into_iter().map(|stmt| match stmt {
+            Stmt::Line(line) => Stmt::Line(canonical_line(&line, "", is_require(&line))),
+            block @ Stmt::Block(_) => block,
+        }));
+    }
+
+    FileSyntax { stmts }
+}
+
+/// Removes duplicate entries and sorts the entries of blocks, like `modfile`'s
+/// `SortBlocks` does before `go mod edit -fmt` formats a file.
+fn cleanup(syntax: &FileSyntax) -> FileSyntax {
+    let duplicates = duplicates(syntax);
+    // like Go, from `go 1.21` on, exclude blocks are sorted semantically
+    let semantic_exclude = syntax
+        .stmts
+        .iter()
+        .find_map(|stmt| match stmt {
+            Stmt::Line(line) if line.verb() == Some("go") => line.values().nth(1),
+            _ => None,
+        })
+        .map_or(false, |go| {
+            compare_versions(&format!("v{go}"), "v1.21").is_ge()
+        });
+
+    let mut stmts: Vec<Stmt> = Vec::with_capacity(syntax.stmts.len());
+    for (i, stmt) in syntax.stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Line(_) if duplicates.contains(&(i, None)) => {
+                // the comments directly above belong to the removed line
+                while matches!(stmts.last(), Some(Stmt::Line(line)) if line.is_comment()) {
+                    stmts.pop();
+                }
+            }
+            Stmt::Line(line) => stmts.push(Stmt::Line(line.clone())),
+            Stmt::Block(block) => {
+                let verb = block.verb().unwrap_or_default();
+                // entries are sorted together with the lines above them
+                let mut entries: Vec<Vec<Line>> = Vec::new();
+                let mut lines: Vec<Line> = Vec::new();
+                for (j, line) in block.lines.iter().enumerate() {
+                    if line.tokens.is_empty() {
+                        lines.push(line.clone());
+                    } else if duplicates.contains(&(i, Some(j))) {
+                        while lines.last().map_or(false, Line::is_comment) {
+                            lines.pop();
+                        }
+                    } else {
+                        lines.push(line.clone());
+                        entries.push(std::mem::take(&mut lines));
+                    }
+                }
+                if DIRECTIVES.contains(&verb) || verb == "use" {
+                    entries.sort_by(|a, b| {
+                        compare_entries(verb, semantic_exclude, &a[a.len() - 1], &b[b.len() - 1])
+                    });
+                }
+                entries.push(lines);
+
+                stmts.push(Stmt::Block(LineBlock {
+                    lines: entries.concat(),
+                    ..block.clone()
+                }));
+            }
+        }
+    }
+
+    FileSyntax { stmts }
+}
+
+/// The positions of duplicate entries, as statement and block line index,
+/// which Go's `go mod edit -fmt` removes: all but the first of equal
+/// `exclude`, `tool` and `ignore` entries and all but the last replacement
+/// of a module version.
+fn duplicates(syntax: &FileSyntax) -> HashSet<(usize, Option<usize>)> {
+    let mut entries: Vec<(&str, Vec<&str>, _)> = Vec::new();
+    for (i, stmt) in syntax.stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Line(line) => {
+                if let Some(verb) = line.verb() {
+                    entries.push((verb, line.values().skip(1).collect(), (i, None)));
+                }
+            }
+            Stmt::Block(block) => {
+                let verb = block.verb().unwrap_or_default();
+                for (j, line) in block.lines.iter().enumerate() {
+                    if !line.tokens.is_empty() {
+                        entries.push((verb, line.values().collect(), (i, Some(j))));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut duplicates = HashSet::new();
+    // later replacements take priority over earlier ones
+    for (verb, args, at) in entries.iter().rev() {
+        if *verb == "replace" {
+            let old: Vec<String> = args
+                .iter()
+                .take_while(|arg| **arg != "=>")
+                .map(|arg| token_value(arg))
+                .collect();
+            if !seen.insert((*verb, old)) {
+                duplicates.insert(*at);
+            }
+        }
+    }
+    for (verb, args, at) in entries {
+        if matches!(verb, "exclude" | "tool" | "ignore")
+            && !seen.insert((verb, args.into_iter().map(token_value).collect()))
+        {
+            duplicates.insert(at);
+        }
+    }
+
+    duplicates
+}
+
+/// Orders the entries of a `verb` block like `modfile`'s `SortBlocks`.
+fn compare_entries(verb: &str, semantic_exclude: bool, a: &Line, b: &Line) -> Ordering {
+    match verb {
+        "exclude" if semantic_exclude && a.tokens.len() == 2 && b.tokens.len() == 2 => {
+            let (a, b) = (&a.tokens, &b.tokens);
+            a[0].text
+                .cmp(&b[0].text)
+                .then_with(|| compare_versions(&a[1].text, &b[1].text))
+        }
+        // newest retractions first
+        "retract" => {
+            let (a, b) = (retract_interval(a), retract_interval(b));
+            compare_versions(b.0, a.0).then_with(|| compare_versions(b.1, a.1))
+        }
+        _ => a.values().cmp(b.values()),
+    }
+}
+
+/// The bounds of a `retract` entry, empty if it is malformed.
+fn retract_interval(line: &Line) -> (&str, &str) {
+    match line.values().collect::<Vec<_>>()[..] {
+        [version] => (version, version),
+        ["[", low, ",", high, "]"] => (low, high),
+        _ => ("", ""),
+    }
+}
+
+/// Compares versions like Go's `semver.Compare`, invalid versions first.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (a.parse::<Version>(), b.parse::<Version>()) {
+        (Ok(a), Ok(b)) => a.cmp_precedence(&b),
+        (a, b) => a.is_ok().cmp(&b.is_ok()),
+    }
+}
+
+/// Formats a block, collapsing it into its comments and a single line if it
+/// has one entry. Blocks without entries are dropped. Blocks of unknown
+/// directives are kept as blocks, as their shape may be significant.
+fn block_stmts(block: &LineBlock) -> Vec<Stmt> {
+    let indirect = block.verb() == Some("require");
+    let known = block
+        .verb()
+        .map_or(false, |verb| DIRECTIVES.contains(&verb) || verb == "use");
+    let entries = block.lines.iter().filter(|l| !l.tokens.is_empty()).count();
+    let last = block.lines.iter().rposition(|l| !l.tokens.is_empty());
+
+    if known {
+        match last {
+            None => return Vec::new(),
+            Some(last) if entries == 1 && block.lines[last + 1..].iter().all(Line::is_blank) => {
+                return collapse(block, last);
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut lines: Vec<Line> = Vec::new();
+    for line in &block.lines {
+        if !line.is_blank() {
+            lines.push(canonical_line(line, "\t", indirect));
+        } else if lines.last().map_or(false, |l| !l.is_blank()) {
+            lines.push(Line::new(Vec::<String>::new()));
+        }
+    }
+    while lines.last().map_or(false, Line::is_blank) {
+        lines.pop();
+    }
+
+    let mut open = canonical_line(&block.open, "", false);
+    if let Some(paren) = open.tokens.last_mut() {
+        paren.space = " ".to_string();
+    }
+
+    vec![Stmt::Block(LineBlock {
+        open,
+        lines,
+        close: Line::new([")"]),
+    })]
+}
+
+/// Collapses `block` into its comments and the single line of its entry at
+/// `last`.
+fn collapse(block: &LineBlock, last: usize) -> Vec<Stmt> {
+    let mut line = block.lines[last].clone();
+    let verb = &block.open.tokens[..block.open.tokens.len() - 1];
+    line.tokens.splice(0..0, verb.iter().cloned());
+
+    // the comment after `(` moves above the line, if the entry has its own
+    let mut open = None;
+    if line.comment.is_none() {
+        line.comment.clone_from(&block.open.comment);
+    } else if let Some(comment) = &block.open.comment {
+        open = Some(Line {
+            comment: Some(Token::new("", &comment.text)),
+            newline: line.newline.clone(),
+            ..Line::default()
+        });
+    }
+
+    // comments inside the block stay above the collapsed line
+    open.iter()
+        .chain(block.lines[..last].iter().filter(|l| l.is_comment()))
+        .chain([&line])
+        .map(|l| Stmt::Line(l.clone()))
+        .collect()
+}
+
+/// Formats a line with `indent`, normalizing `// indirect` markers if
+/// `indirect` is set.
+fn canonical_line(line: &Line, indent: &str, indirect: bool) -> Line {
+    let mut sep = "";
+    let mut tokens = Vec::with_capacity(line.tokens.len());
+    for (i, token) in line.tokens.iter().enumerate() {
+        let text = token.text.as_str();
+        if matches!(text, "," | ")" | "]" | "}") {
+            sep = "";
+        }
+        tokens.push(Token::new(if i == 0 { indent } else { sep }, text));
+        sep = if matches!(text, "(" | "[" | "{") {
+            ""
+        } else {
+            " "
+        };
+    }
+
+    let comment = line.comment.as_ref().map(|comment| {
+        let text = if indirect && !tokens.is_empty() {
+            normalize_indirect(&comment.text)
+        } else {
+            comment.text.clone()
+        };
+        Token::new(if tokens.is_empty() { indent } else { " " }, text)
+    });
+
+    Line {
+        tokens,
+        comment,
+        trailing: String::new(),
+        newline: "\n".to_string(),
+    }
+}
+
+fn is_require(line: &Line) -> bool {
+    line.verb() == Some("require")
+}
+
+/// Rewrites `//indirect` style comments to `// indirect`, keeping any text
+/// following `indirect;`.
+fn normalize_indirect(comment: &str) -> String {
+    match indirect_note(comment) {
+        Some("") => "// indirect".to_string(),
+        Some(note) => format!("// indirect; {note}"),
+        None => comment.to_string(),
+    }
+}
+
+/// Renders the directives, ignoring [`GoMod::syntax`]. The top-level comments
+/// are written above the `module` directive, followed by the deprecation
+/// notice, the comments of entries and retract rationales around their
+/// entries.
+impl fmt::Display for GoMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut comments = self.comment.clone();
+        // unless the comments already hold it, the deprecation notice follows
+        // them, directly above the `module` directive
+        if let Some(deprecated) = &self.deprecated {
+            if deprecation(&comments.join("\n")).as_ref() != Some(deprecated) {
+                // in a paragraph of its own
+                if comments.last().map_or(false, |c| !c.is_empty()) {
+                    comments.push(String::new());
+                }
+                comments.extend(
+                    format!("Deprecated: {deprecated}")
+                        .lines()
+                        .map(String::from),
+                );
+            }
+        }
+        let mut stmts: Vec<Stmt> = comments
+            .iter()
+            .map(|comment| Stmt::Line(comment_line(comment)))
+            .collect();
+        // blocks with a single entry are collapsed by `canonical`
+        let mut push = |verb: &str, lines: Vec<Line>| {
+            if !lines.is_empty() {
+                stmts.push(Stmt::Block(LineBlock {
+                    open: Line::new([verb, "("]),
+                    lines,
+                    close: Line::new([")"]),
+                }));
+            }
+        };
+
+        let mut godebug: Vec<_> = self.godebug.iter().collect();
+        godebug.sort();
+
+        if !self.module.is_empty() {
+            push("module", vec![Line::new([auto_quote(&self.module)])]);
+        }
+        push("go", self.go.iter().map(|go| Line::new([go])).collect());
+        push(
+            "toolchain",
+            self.toolchain.iter().map(|t| Line::new([t])).collect(),
+        );
+        push(
+            "godebug",
+            godebug
+                .into_iter()
+                .map(|(key, value)| Line::new([format!("{key}={value}")]))
+                .collect(),
+        );
+        push("tool", entries(&self.tool, |tool| &tool.comments));
+        push(
+            "require",
+            self.require
+                .iter()
+                .flat_map(|dependency| {
+                    let comments = Comments {
+                        suffix: dependency.suffix(),
+                        ..dependency.comments.clone()
+                    };
+                    commented(Line::new([dependency.module.to_string()]), &comments)
+                })
+                .collect(),
+        );
+        push(
+            "exclude",
+            self.exclude
+                .iter()
+                .flat_map(|dependency| {
+                    commented(
+                        Line::new([dependency.module.to_string()]),
+                        &dependency.comments,
+                    )
+                })
+                .collect(),
+        );
+        push(
+            "replace",
+            entries(&self.replace, |replacement| &replacement.comments),
+        );
+        push(
+            "retract",
+            self.retract
+                .iter()
+                .flat_map(|retract| {
+                    let rationale = retract.rationale.as_deref().unwrap_or_default();
+                    rationale
+                        .lines()
+                        .map(comment_line)
+                        .chain([Line::new([retract.to_string()])])
+                })
+                .collect(),
+        );
+        push("ignore", entries(&self.ignore, |ignore| &ignore.comments));
+        stmts.extend(self.unknown.iter().map(UnknownDirective::stmt));
+
+        write!(f, "{}", canonical(&FileSyntax { stmts }))
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            auto_quote(&self.module_path),
+            auto_quote(&self.version)
+        )
+    }
+}
+
+impl ModuleDependency {
+    /// The text of the same-line comment, prefixed by `indirect` markers.
+    fn suffix(&self) -> Option<String> {
+        if !self.indirect {
+            return self.comments.suffix.clone();
+        }
+        Some(self.comments.suffix.as_ref().map_or_else(
+            || "indirect".to_string(),
+            |suffix| format!("indirect; {suffix}"),
+        ))
+    }
+}
+
+/// Renders the `require` entry with its same-line comment, e.g.
+/// `golang.org/x/net v0.20.0 // indirect; pinned`.
+impl fmt::Display for ModuleDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.module)?;
+        if let Some(suffix) = self.suffix() {
+            write!(f, " // {suffix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the `replace` entry, e.g. `golang.org/x/net v0.19.0 => ../net`.
+impl fmt::Display for ModuleReplacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", auto_quote(&self.module_path))?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", auto_quote(version))?;
+        }
+        write!(f, " => {}", self.replacement)
+    }
+}
+
+/// Renders the `tool` or `ignore` entry, its path.
+impl fmt::Display for PathEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", auto_quote(&self.path))
+    }
+}
+
+impl fmt::Display for Replacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FilePath(path) => write!(f, "{}", auto_quote(path)),
+            Self::Module(module) => write!(f, "{module}"),
+        }
+    }
+}
+
+/// Renders the versions of the `retract` entry, see [`VersionInterval`]. The
+/// rationale is written as comment above the entry by [`GoMod`].
+impl fmt::Display for ModuleRetract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.interval)
+    }
+}
+
+/// Renders the directive, in block form if it has a block.
+impl fmt::Display for UnknownDirective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stmts = vec![self.stmt()];
+        write!(f, "{}", canonical(&FileSyntax { stmts }))
+    }
+}
+
+impl UnknownDirective {
+    fn stmt(&self) -> Stmt {
+        let mut tokens: Vec<String> = std::iter::once(self.keyword.clone())
+            .chain(self.args.iter().map(|arg| auto_quote(arg)))
+            .collect();
+
+        match &self.block {
+            None => Stmt::Line(Line::new(tokens)),
+            Some(lines) => {
+                tokens.push("(".to_string());
+                Stmt::Block(LineBlock {
+                    open: Line::new(tokens),
+                    lines: lines
+                        .iter()
+                        .map(|args| Line::new(args.iter().map(|arg| auto_quote(arg))))
+                        .collect(),
+                    close: Line::new([")"]),
+                })
+            }
+        }
+    }
+}
+
+/// Renders the retracted versions, e.g. `v1.0.0` or `[v1.0.0, v1.0.5]`.
+impl fmt::Display for VersionInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(version) => write!(f, "{}", auto_quote(version)),
+            Self::Range(low, high) => write!(f, "[{}, {}]", auto_quote(low), auto_quote(high)),
+        }
+    }
+}
+
+/// A line holding nothing but the comment `text`.
+fn comment_line(text: &str) -> Line {
+    Line {
+        comment: Some(Token::new("", format!("// {text}").trim_end())),
+        ..Line::default()
+    }
+}
+
+/// The comment lines above an entry, followed by its `line` ending with the
+/// suffix comment.
+fn commented(mut line: Line, comments: &Comments) -> Vec<Line> {
+    if let Some(suffix) = &comments.suffix {
+        line.comment = Some(Token::new(" ", format!("// {suffix}")));
+    }
+
+    let mut lines: Vec<Line> = comments.before.iter().map(|c| comment_line(c)).collect();
+    lines.push(line);
+    lines
+}
+
+fn entries<T: fmt::Display>(values: &[T], comments: impl Fn(&T) -> &Comments) -> Vec<Line> {
+    values
+        .iter()
+        .flat_map(|value| commented(Line::new([value.to_string()]), comments(value)))
+        .collect()
+}
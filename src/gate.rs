@@ -0,0 +1,135 @@
+//! Checks of directives against the `go` version declaring the language
+//! version of a module: the Go toolchain rejects directives introduced after
+//! it, e.g. `toolchain` in a module declaring `go 1.20`.
+
+use crate::{GoMod, GoVersion};
+use std::fmt;
+
+/// The directives introduced after modules, with the `go` version adding them.
+const GATES: [(&str, &str); 5] = [
+    ("retract", "1.16"),
+    ("toolchain", "1.21"),
+    ("godebug", "1.23"),
+    ("tool", "1.24"),
+    ("ignore", "1.25"),
+];
+
+/// The `go` version assumed for modules without `go` directive, like the Go
+/// toolchain does since Go 1.17.
+const DEFAULT_GO: &str = "1.16";
+
+/// An error for a directive which the declared `go` version does not support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoVersionError {
+    /// The directive, e.g. `toolchain`.
+    pub directive: String,
+    /// The minimum `go` version supporting the directive.
+    pub required: String,
+    /// The declared `go` version, or the one assumed without `go` directive.
+    pub go: String,
+}
+
+impl fmt::Display for GoVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: requires go >= {}, but go {} is declared",
+            self.directive, self.required, self.go
+        )
+    }
+}
+
+impl std::error::Error for GoVersionError {}
+
+impl GoMod {
+    /// Checks that the declared `go` version supports the directives in use,
+    /// returning an error for each one it does not.
+    ///
+    /// Without `go` directive, `go 1.16` is assumed like by the Go toolchain.
+    /// An unparsable `go` version is not checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example.com/app
+    ///
+    /// go 1.21
+    ///
+    /// toolchain go1.22.0
+    ///
+    /// tool golang.org/x/tools/cmd/stringer
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    /// let errors = go_mod.check_go_version();
+    ///
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(
+    ///     errors[0].to_string(),
+    ///     "tool: requires go >= 1.24, but go 1.21 is declared"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn check_go_version(&self) -> Vec<GoVersionError> {
+        let go = self.go.as_deref().unwrap_or(DEFAULT_GO);
+        let Ok(declared) = go.parse::<GoVersion>() else {
+            return Vec::new();
+        };
+
+        self.gates()
+            .filter(|(_, required)| {
+                required
+                    .parse::<GoVersion>()
+                    .map_or(false, |required| declared.cmp_precedence(&required).is_lt())
+            })
+            .map(|(directive, required)| GoVersionError {
+                directive: directive.to_string(),
+                required: required.to_string(),
+                go: go.to_string(),
+            })
+            .collect()
+    }
+
+    /// The minimum `go` version supporting all directives in use, or `None`
+    /// if any version does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example.com/app
+    ///
+    /// toolchain go1.22.0
+    ///
+    /// retract v1.0.0
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    ///
+    /// assert_eq!(go_mod.min_go_version(), Some("1.21"));
+    /// ```
+    #[must_use]
+    pub fn min_go_version(&self) -> Option<&'static str> {
+        self.gates().map(|(_, required)| required).last()
+    }
+
+    /// The gated directives in use with the `go` versions they require, in
+    /// ascending order.
+    fn gates(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        GATES.into_iter().filter(|(directive, _)| match *directive {
+            "retract" => !self.retract.is_empty(),
+            "toolchain" => self.toolchain.is_some(),
+            "godebug" => !self.godebug.is_empty(),
+            "tool" => !self.tool.is_empty(),
+            "ignore" => !self.ignore.is_empty(),
+            _ => false,
+        })
+    }
+}
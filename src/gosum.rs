@@ -0,0 +1,156 @@
+use crate::parser::{expected, skip_line, Input, State, WHITESPACES};
+use crate::ParseError;
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+use winnow::ascii::{line_ending, multispace0, space0, space1};
+use winnow::combinator::{alt, cut_err, eof, preceded, repeat_till};
+use winnow::error::{ErrMode, StrContext};
+use winnow::stream::Stream;
+use winnow::token::take_till;
+use winnow::{ModalResult, Parser};
+
+/// A parsed `go.sum` file.
+///
+/// # Example
+///
+/// ```rust
+/// use gomod_parser::GoSum;
+/// use std::str::FromStr;
+///
+/// let input = r#"
+/// golang.org/x/net v0.20.0 h1:aCL9BSgETF1k+blQaYUBx9hJ9LOGP3gAVemcZlf1Kpo=
+/// golang.org/x/net v0.20.0/go.mod h1:z8BVo6PvndSri0LbOE3hAn0apkU+1YvI6E70E9jsnvY=
+/// "#;
+///
+/// let go_sum = GoSum::from_str(input).unwrap();
+///
+/// assert_eq!(go_sum.entries.len(), 2);
+/// assert_eq!(go_sum.entries[1].module_path, "golang.org/x/net".to_string());
+/// assert_eq!(go_sum.entries[1].version, "v0.20.0".to_string());
+/// assert!(go_sum.entries[1].go_mod);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GoSum {
+    /// The checksums in file order.
+    pub entries: Vec<GoSumEntry>,
+}
+
+/// A `go.sum` line, serialized as
+/// `{"module_path": "golang.org/x/net", "version": "v0.20.0", "go_mod": true, "hash": "h1:..."}`.
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GoSumEntry {
+    pub module_path: String,
+    /// The version, without the `/go.mod` suffix.
+    pub version: String,
+    /// Whether the hash is of the module's `go.mod` file only, rather than of
+    /// its whole content.
+    pub go_mod: bool,
+    /// The hash including its `h1:` prefix.
+    pub hash: String,
+}
+
+impl GoSum {
+    /// Parses `input` on a best-effort basis, skipping malformed lines and
+    /// reporting them as diagnostics, like [`GoMod::parse_with_diagnostics`].
+    ///
+    /// [`GoMod::parse_with_diagnostics`]: crate::GoMod::parse_with_diagnostics
+    #[must_use]
+    pub fn parse_with_diagnostics(input: &str) -> (Self, Vec<ParseError>) {
+        let diagnostics = RefCell::new(Vec::new());
+        let state = State::recovering(input, &diagnostics);
+
+        let res = match gosum.parse(Input { input, state }) {
+            Ok(entries) => Self { entries },
+            Err(e) => {
+                diagnostics.borrow_mut().push(e.into());
+                Self::default()
+            }
+        };
+
+        (res, diagnostics.into_inner())
+    }
+}
+
+impl FromStr for GoSum {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let state = State::strict(input);
+        let entries = gosum.parse(Input { input, state })?;
+
+        Ok(Self { entries })
+    }
+}
+
+impl fmt::Display for GoSum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.entries
+            .iter()
+            .try_for_each(|entry| writeln!(f, "{entry}"))
+    }
+}
+
+/// Renders the line, e.g. `golang.org/x/net v0.20.0/go.mod h1:...`.
+impl fmt::Display for GoSumEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.module_path, self.version)?;
+        if self.go_mod {
+            write!(f, "/go.mod")?;
+        }
+        write!(f, " {}", self.hash)
+    }
+}
+
+fn gosum(input: &mut Input<'_>) -> ModalResult<Vec<GoSumEntry>> {
+    let (res, _): (Vec<Option<GoSumEntry>>, _) = repeat_till(
+        0..,
+        |i: &mut Input<'_>| {
+            let start = i.checkpoint();
+            match gosum_entry.parse_next(i) {
+                Ok(entry) => Ok(Some(entry)),
+                Err(ErrMode::Backtrack(e) | ErrMode::Cut(e)) if i.state.report(i.input, &e) => {
+                    i.reset(&start);
+                    let _ = (multispace0, skip_line).parse_next(i)?;
+                    Ok(None)
+                }
+                Err(e) => Err(e),
+            }
+        },
+        (multispace0, eof),
+    )
+    .parse_next(input)?;
+
+    Ok(res.into_iter().flatten().collect())
+}
+
+/// Parses a `<module> <version>[/go.mod] h1:<hash>` line.
+fn gosum_entry(input: &mut Input<'_>) -> ModalResult<GoSumEntry> {
+    let _ = multispace0.parse_next(input)?;
+
+    let (module_path, version, hash) = cut_err((
+        take_till(1.., WHITESPACES).context(expected("module path")),
+        preceded(space1, take_till(1.., WHITESPACES)).context(expected("version")),
+        preceded(space1, ("h1:", take_till(1.., WHITESPACES)).take())
+            .context(expected("`h1:` hash")),
+    ))
+    .context(StrContext::Label("checksum"))
+    .parse_next(input)?;
+    let _ = cut_err((space0, alt((line_ending, eof))))
+        .context(StrContext::Label("checksum"))
+        .context(expected("newline"))
+        .parse_next(input)?;
+
+    let (version, go_mod) = version
+        .strip_suffix("/go.mod")
+        .map_or((version, false), |version| (version, true));
+
+    Ok(GoSumEntry {
+        module_path: module_path.to_string(),
+        version: version.to_string(),
+        go_mod,
+        hash: hash.to_string(),
+    })
+}
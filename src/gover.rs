@@ -0,0 +1,184 @@
+use crate::parser::expected;
+use crate::version::number;
+use crate::ParseError;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use winnow::combinator::{cut_err, eof, opt, preceded};
+use winnow::error::StrContext;
+use winnow::token::take_while;
+use winnow::{ModalResult, Parser};
+
+/// A Go language or release version, as written in the `go` directive, like
+/// Go's `go/version` package handles them.
+///
+/// Versions are written `MAJOR[.MINOR[.PATCH]]` or `MAJOR.MINOR[PRERELEASE]`,
+/// where the prerelease is a lowercase kind and an optional number, e.g.
+/// `1.21rc1`. Since Go 1.21, a language version like `1.21` precedes its
+/// prereleases, which precede its first release `1.21.0`. Before, `1.20` was
+/// the first release itself, so it equals `1.20.0` in precedence.
+///
+/// # Example
+///
+/// ```rust
+/// use gomod_parser::GoVersion;
+///
+/// let lang: GoVersion = "1.21".parse().unwrap();
+/// let rc: GoVersion = "1.21rc1".parse().unwrap();
+/// let release: GoVersion = "1.21.0".parse().unwrap();
+///
+/// assert!(lang < rc && rc < release);
+/// assert_eq!(release.lang(), lang);
+/// assert_eq!(GoVersion::from_toolchain("go1.22.0-custom"), "1.22.0".parse().ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GoVersion {
+    pub major: u64,
+    /// The minor number, `None` for the language version `1`.
+    pub minor: Option<u64>,
+    /// The patch number, `None` for language versions and prereleases.
+    pub patch: Option<u64>,
+    /// The prerelease, e.g. `rc1` or `beta2`.
+    pub prerelease: Option<String>,
+}
+
+impl GoVersion {
+    /// The version of a toolchain name, like `go1.21.1`, `go1.22.0-custom`
+    /// or `gccgo-go1.21`, following Go's `gover.FromToolchain`. Returns
+    /// `None`, if `name` is no toolchain name.
+    #[must_use]
+    pub fn from_toolchain(name: &str) -> Option<Self> {
+        if name.contains(['\\', '/']) {
+            return None;
+        }
+        let version = match name.strip_prefix("go") {
+            Some(version) => version,
+            None => &name[name.find("-go")? + 3..],
+        };
+        // custom builds append a suffix
+        let end = version.find([' ', '\t', '-']).unwrap_or(version.len());
+
+        version[..end].parse().ok()
+    }
+
+    /// The language version, e.g. `1.21` of `1.21rc1` and `1.21.3`.
+    #[must_use]
+    pub const fn lang(&self) -> Self {
+        Self {
+            major: self.major,
+            minor: self.minor,
+            patch: None,
+            prerelease: None,
+        }
+    }
+
+    /// Returns `true` for prerelease versions, like `1.21rc1`.
+    #[must_use]
+    pub const fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    /// Compares the precedence of versions like Go's `gover.Compare`, so
+    /// `1.20` equals `1.20.0`.
+    #[must_use]
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+
+    /// The numbers and the prerelease kind and number, compared in order.
+    /// Missing parts precede present ones.
+    fn key(&self) -> (u64, u64, Option<u64>, Option<&str>, Option<u64>) {
+        // like Go, `1` is read as `1.0`
+        let minor = self.minor.unwrap_or(0);
+        // before Go 1.21, a language version is its first release
+        let patch = self
+            .patch
+            .or_else(|| (minor < 21 && self.prerelease.is_none()).then_some(0));
+        let (kind, number) = self.prerelease.as_deref().map_or((None, None), |pre| {
+            let (kind, number) =
+                pre.split_at(pre.find(|c: char| c.is_ascii_digit()).unwrap_or(pre.len()));
+            (Some(kind), number.parse().ok())
+        });
+
+        (self.major, minor, patch, kind, number)
+    }
+}
+
+impl FromStr for GoVersion {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        go_version
+            .context(StrContext::Label("go version"))
+            .parse(input)
+            .map_err(|e| ParseError::new(input, e.offset(), e.inner()))
+    }
+}
+
+impl Ord for GoVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.minor.cmp(&other.minor))
+            .then_with(|| self.patch.cmp(&other.patch))
+    }
+}
+
+impl PartialOrd for GoVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for GoVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        if let Some(prerelease) = &self.prerelease {
+            write!(f, "{prerelease}")?;
+        }
+        Ok(())
+    }
+}
+
+fn go_version(input: &mut &str) -> ModalResult<GoVersion> {
+    let major = number
+        .context(expected("major version"))
+        .parse_next(input)?;
+    let minor = opt(preceded(
+        '.',
+        cut_err(number.context(expected("minor version"))),
+    ))
+    .parse_next(input)?;
+    let patch = if minor.is_some() {
+        opt(preceded(
+            '.',
+            cut_err(number.context(expected("patch version"))),
+        ))
+        .parse_next(input)?
+    } else {
+        None
+    };
+
+    // like Go, only language versions with minor may have a prerelease
+    let prerelease = if minor.is_some() && patch.is_none() {
+        opt((take_while(1.., 'a'..='z'), opt(number)).take())
+            .map(|prerelease| prerelease.map(ToString::to_string))
+            .parse_next(input)?
+    } else {
+        None
+    };
+
+    let _ = cut_err(eof.context(expected("end of version"))).parse_next(input)?;
+
+    Ok(GoVersion {
+        major,
+        minor,
+        patch,
+        prerelease,
+    })
+}
@@ -0,0 +1,107 @@
+use crate::parser::{gowork, Directive, Input, State};
+use crate::{ModuleReplacement, ParseError};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+use winnow::Parser;
+
+/// A parsed `go.work` workspace file.
+///
+/// # Example
+///
+/// ```rust
+/// use gomod_parser::GoWork;
+/// use std::str::FromStr;
+///
+/// let input = r#"
+/// go 1.22
+///
+/// use (
+///     ./api
+///     ./cmd/server
+/// )
+/// "#;
+///
+/// let go_work = GoWork::from_str(input).unwrap();
+///
+/// assert_eq!(go_work.go, Some("1.22".to_string()));
+/// assert_eq!(
+///     go_work.uses,
+///     vec!["./api".to_string(), "./cmd/server".to_string()]
+/// );
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GoWork {
+    /// The text of the top-level comments.
+    pub comment: Vec<String>,
+    /// The `go` version.
+    pub go: Option<String>,
+    /// The `toolchain` name.
+    pub toolchain: Option<String>,
+    /// The `godebug` settings, by key.
+    pub godebug: HashMap<String, String>,
+    /// The module directories of the workspace, from the `use` directives.
+    #[cfg_attr(feature = "serde", serde(rename = "use"))]
+    pub uses: Vec<String>,
+    pub replace: Vec<ModuleReplacement>,
+}
+
+impl GoWork {
+    /// Parses `input` on a best-effort basis, skipping invalid lines and
+    /// reporting them as diagnostics, like [`GoMod::parse_with_diagnostics`].
+    ///
+    /// [`GoMod::parse_with_diagnostics`]: crate::GoMod::parse_with_diagnostics
+    #[must_use]
+    pub fn parse_with_diagnostics(input: &str) -> (Self, Vec<ParseError>) {
+        let diagnostics = RefCell::new(Vec::new());
+        let state = State::recovering(input, &diagnostics);
+
+        let res = match gowork.parse(Input { input, state }) {
+            Ok(mut directives) => Self::from_directives(&mut directives),
+            Err(e) => {
+                diagnostics.borrow_mut().push(e.into());
+                Self::default()
+            }
+        };
+
+        (res, diagnostics.into_inner())
+    }
+
+    fn from_directives(directives: &mut [Directive]) -> Self {
+        let mut res = Self::default();
+
+        for directive in directives {
+            match directive {
+                Directive::Comment(d) => res.comment.push((**d).to_string()),
+                Directive::Go(d) => res.go = Some((**d).to_string()),
+                Directive::GoDebug(d) => res.godebug.extend((*d).clone()),
+                Directive::Toolchain(d) => res.toolchain = Some((**d).to_string()),
+                Directive::Use(d) => res.uses.append(d),
+                Directive::Replace(d) => res.replace.append(d),
+                // not parsed in go.work files
+                Directive::Module(_)
+                | Directive::Tool(_)
+                | Directive::Require(_)
+                | Directive::Exclude(_)
+                | Directive::Retract(_)
+                | Directive::Ignore(_)
+                | Directive::Unknown(_)
+                | Directive::Skipped => {}
+            }
+        }
+
+        res
+    }
+}
+
+impl FromStr for GoWork {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let state = State::strict(input);
+        let mut directives = gowork.parse(Input { input, state })?;
+
+        Ok(Self::from_directives(&mut directives))
+    }
+}
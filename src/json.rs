@@ -0,0 +1,301 @@
+//! Conversion from and to the JSON schema of `go mod edit -json`.
+
+use crate::{
+    Comments, GoMod, Module, ModuleDependency, ModuleReplacement, ModuleRetract, PathEntry,
+    Replacement, VersionInterval,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::ser::PrettyFormatter;
+use serde_json::Serializer;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileJson {
+    module: ModuleJson,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    go: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    toolchain: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    godebug: Vec<GodebugJson>,
+    #[serde(default)]
+    require: Option<Vec<RequireJson>>,
+    #[serde(default)]
+    exclude: Option<Vec<VersionJson>>,
+    #[serde(default)]
+    replace: Option<Vec<ReplaceJson>>,
+    #[serde(default)]
+    retract: Option<Vec<RetractJson>>,
+    #[serde(default)]
+    tool: Option<Vec<PathJson>>,
+    #[serde(default)]
+    ignore: Option<Vec<PathJson>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ModuleJson {
+    path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    deprecated: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GodebugJson {
+    key: String,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RequireJson {
+    path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    version: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    indirect: bool,
+}
+
+/// Go's `module.Version`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VersionJson {
+    path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReplaceJson {
+    old: VersionJson,
+    new: VersionJson,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RetractJson {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    low: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    high: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    rationale: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PathJson {
+    path: String,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl GoMod {
+    /// Serializes to the JSON printed by `go mod edit -json`, e.g.
+    ///
+    /// ```json
+    /// {
+    ///     "Module": {
+    ///         "Path": "github.com/example"
+    ///     },
+    ///     "Go": "1.21",
+    ///     "Require": [
+    ///         {
+    ///             "Path": "golang.org/x/net",
+    ///             "Version": "v0.20.0",
+    ///             "Indirect": true
+    ///         }
+    ///     ],
+    ///     "Exclude": null,
+    ///     "Replace": null,
+    ///     "Retract": null,
+    ///     "Tool": null,
+    ///     "Ignore": null
+    /// }
+    /// ```
+    ///
+    /// Like the Go toolchain, it is indented by tabs and ends with a newline.
+    /// As their order is not kept, `godebug` settings are sorted by key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    /// use std::str::FromStr;
+    ///
+    /// let go_mod = GoMod::from_str("module github.com/example\n\ngo 1.21\n").unwrap();
+    ///
+    /// let json = go_mod.to_go_json();
+    ///
+    /// assert!(json.starts_with("{\n\t\"Module\": {\n\t\t\"Path\": \"github.com/example\"\n\t},\n"));
+    /// assert_eq!(GoMod::from_go_json(&json).unwrap(), go_mod);
+    /// ```
+    #[must_use]
+    pub fn to_go_json(&self) -> String {
+        let mut godebug: Vec<_> = self
+            .godebug
+            .iter()
+            .map(|(key, value)| GodebugJson {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        godebug.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let file = FileJson {
+            module: ModuleJson {
+                path: self.module.clone(),
+                deprecated: self.deprecated.clone().unwrap_or_default(),
+            },
+            go: self.go.clone().unwrap_or_default(),
+            toolchain: self.toolchain.clone().unwrap_or_default(),
+            godebug,
+            require: non_empty(&self.require, |dependency| RequireJson {
+                path: dependency.module.module_path.clone(),
+                version: dependency.module.version.clone(),
+                indirect: dependency.indirect,
+            }),
+            exclude: non_empty(&self.exclude, |dependency| {
+                VersionJson::from(&dependency.module)
+            }),
+            replace: non_empty(&self.replace, |replacement| ReplaceJson {
+                old: VersionJson {
+                    path: replacement.module_path.clone(),
+                    version: replacement.version.clone().unwrap_or_default(),
+                },
+                new: match &replacement.replacement {
+                    Replacement::FilePath(path) => VersionJson {
+                        path: path.clone(),
+                        version: String::new(),
+                    },
+                    Replacement::Module(module) => VersionJson::from(module),
+                },
+            }),
+            retract: non_empty(&self.retract, |retract| {
+                let (low, high) = match &retract.interval {
+                    VersionInterval::Single(version) => (version, version),
+                    VersionInterval::Range(low, high) => (low, high),
+                };
+                RetractJson {
+                    low: low.clone(),
+                    high: high.clone(),
+                    rationale: retract.rationale.clone().unwrap_or_default(),
+                }
+            }),
+            tool: non_empty(&self.tool, |tool| PathJson {
+                path: tool.path.clone(),
+            }),
+            ignore: non_empty(&self.ignore, |ignore| PathJson {
+                path: ignore.path.clone(),
+            }),
+        };
+
+        let mut out = Vec::new();
+        let mut serializer =
+            Serializer::with_formatter(&mut out, PrettyFormatter::with_indent(b"\t"));
+        // serializing plain structs of strings into a `Vec` cannot fail
+        file.serialize(&mut serializer).unwrap_or_default();
+        out.push(b'\n');
+
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    /// Deserializes the JSON printed by `go mod edit -json`.
+    ///
+    /// A `Replace` with a `New` module without version is a file path
+    /// replacement, a `Retract` with equal `Low` and `High` a single version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not valid JSON of that schema.
+    pub fn from_go_json(json: &str) -> Result<Self, serde_json::Error> {
+        let file: FileJson = serde_json::from_str(json)?;
+
+        Ok(Self {
+            module: file.module.path,
+            deprecated: Some(file.module.deprecated).filter(|deprecated| !deprecated.is_empty()),
+            go: Some(file.go).filter(|go| !go.is_empty()),
+            toolchain: Some(file.toolchain).filter(|toolchain| !toolchain.is_empty()),
+            godebug: file
+                .godebug
+                .into_iter()
+                .map(|godebug| (godebug.key, godebug.value))
+                .collect(),
+            require: list(file.require, |require| ModuleDependency {
+                module: Module {
+                    module_path: require.path,
+                    version: require.version,
+                },
+                indirect: require.indirect,
+                comments: Comments::default(),
+            }),
+            exclude: list(file.exclude, |exclude| ModuleDependency {
+                module: exclude.into(),
+                indirect: false,
+                comments: Comments::default(),
+            }),
+            replace: list(file.replace, |replace| ModuleReplacement {
+                module_path: replace.old.path,
+                version: Some(replace.old.version).filter(|version| !version.is_empty()),
+                replacement: if replace.new.version.is_empty() {
+                    Replacement::FilePath(replace.new.path)
+                } else {
+                    Replacement::Module(replace.new.into())
+                },
+                comments: Comments::default(),
+            }),
+            retract: list(file.retract, |retract| ModuleRetract {
+                interval: if retract.low == retract.high {
+                    VersionInterval::Single(retract.low)
+                } else {
+                    VersionInterval::Range(retract.low, retract.high)
+                },
+                rationale: Some(retract.rationale).filter(|rationale| !rationale.is_empty()),
+            }),
+            tool: list(file.tool, PathEntry::from),
+            ignore: list(file.ignore, PathEntry::from),
+            ..Self::default()
+        })
+    }
+}
+
+impl From<&Module> for VersionJson {
+    fn from(module: &Module) -> Self {
+        Self {
+            path: module.module_path.clone(),
+            version: module.version.clone(),
+        }
+    }
+}
+
+impl From<VersionJson> for Module {
+    fn from(version: VersionJson) -> Self {
+        Self {
+            module_path: version.path,
+            version: version.version,
+        }
+    }
+}
+
+impl From<PathJson> for PathEntry {
+    fn from(path: PathJson) -> Self {
+        Self {
+            path: path.path,
+            comments: Comments::default(),
+        }
+    }
+}
+
+/// Maps `values`, `None` standing for an empty list like Go's `nil` slices.
+fn non_empty<T, U>(values: &[T], f: impl FnMut(&T) -> U) -> Option<Vec<U>> {
+    (!values.is_empty()).then(|| values.iter().map(f).collect())
+}
+
+fn list<T, U>(values: Option<Vec<T>>, f: impl FnMut(T) -> U) -> Vec<U> {
+    values.unwrap_or_default().into_iter().map(f).collect()
+}
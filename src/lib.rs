@@ -3,7 +3,7 @@
 //! # Example
 //!
 //! ```rust
-//! use gomod_parser::{GoMod, Module, ModuleDependency};
+//! use gomod_parser::{Comments, GoMod, Module, ModuleDependency};
 //! use std::str::FromStr;
 //!
 //! let input = r#"
@@ -25,7 +25,8 @@
 //!             module_path: "golang.org/x/net".to_string(),
 //!             version: "v0.20.0".to_string()
 //!         },
-//!         indirect: false
+//!         indirect: false,
+//!         comments: Comments::default(),
 //!     }]
 //! );
 //! ```
@@ -34,38 +35,213 @@
 #![warn(clippy::nursery)]
 #![warn(clippy::cargo)]
 
-use crate::parser::{gomod, Directive};
+pub use crate::error::ParseError;
+pub use crate::gate::GoVersionError;
+pub use crate::gosum::{GoSum, GoSumEntry};
+pub use crate::gover::GoVersion;
+pub use crate::gowork::GoWork;
+use crate::parser::{gomod, Directive, Input, State};
+pub use crate::path::PathError;
+pub use crate::pseudo::PseudoVersion;
+pub use crate::replace::ReplaceError;
+use crate::syntax::FileSyntax;
+pub use crate::version::Version;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::str::FromStr;
 use winnow::Parser;
 
 mod combinator;
+mod comment;
+mod edit;
+mod error;
+pub mod format;
+mod gate;
+mod gosum;
+mod gover;
+mod gowork;
+#[cfg(feature = "json")]
+mod json;
 pub mod parser;
-
-#[derive(Debug, Default, PartialEq, Eq)]
+pub mod path;
+mod pseudo;
+mod query;
+mod quote;
+mod replace;
+pub mod syntax;
+mod version;
+
+/// A parsed `go.mod` file.
+///
+/// With the `serde` feature, it serializes to an object with the field names
+/// below, e.g. `{"module": "github.com/example", "go": "1.21", ...}`.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GoMod {
+    /// The text of the top-level comments not attached to an entry: those
+    /// standing alone and those of single-line directives and blocks.
     pub comment: Vec<String>,
+    /// The module path.
     pub module: String,
+    /// The deprecation message of the module, from a `Deprecated:` paragraph
+    /// in the comments above or beside the `module` directive.
+    pub deprecated: Option<String>,
+    /// The `go` version.
     pub go: Option<String>,
+    /// The `godebug` settings, by key.
     pub godebug: HashMap<String, String>,
-    pub tool: Vec<String>,
+    /// The `tool` package paths.
+    pub tool: Vec<PathEntry>,
+    /// The `toolchain` name.
     pub toolchain: Option<String>,
     pub require: Vec<ModuleDependency>,
     pub exclude: Vec<ModuleDependency>,
     pub replace: Vec<ModuleReplacement>,
     pub retract: Vec<ModuleRetract>,
-    pub ignore: Vec<String>,
+    /// The `ignore` directory paths.
+    pub ignore: Vec<PathEntry>,
+    /// The directives unknown to this crate, e.g. those of newer Go versions,
+    /// in the order of the file.
+    pub unknown: Vec<UnknownDirective>,
+    /// The lossless syntax tree of the parsed file.
+    ///
+    /// The editing methods, like [`GoMod::add_require`], keep it in sync with
+    /// the other fields, so rendering it writes the edited file while
+    /// preserving its comments, blocks and ordering. Direct changes to the
+    /// other fields are not reflected in it.
+    ///
+    /// It is skipped by `serde` and empty after deserializing.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub syntax: FileSyntax,
 }
 
-impl std::str::FromStr for GoMod {
-    type Err = String;
+/// `GoMod`s are equal, if their directives are, regardless of the formatting
+/// kept in [`GoMod::syntax`].
+impl PartialEq for GoMod {
+    fn eq(&self, other: &Self) -> bool {
+        self.comment == other.comment
+            && self.module == other.module
+            && self.deprecated == other.deprecated
+            && self.go == other.go
+            && self.godebug == other.godebug
+            && self.tool == other.tool
+            && self.toolchain == other.toolchain
+            && self.require == other.require
+            && self.exclude == other.exclude
+            && self.replace == other.replace
+            && self.retract == other.retract
+            && self.ignore == other.ignore
+            && self.unknown == other.unknown
+    }
+}
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
+impl Eq for GoMod {}
+
+impl GoMod {
+    /// Parses `input` in `mode`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::{GoMod, ParseMode};
+    ///
+    /// let input = r#"
+    /// module github.com/example
+    ///
+    /// toolchain go1.30.0 with-extras
+    ///
+    /// require golang.org/x/net v0.20.0
+    /// "#;
+    ///
+    /// assert!(GoMod::parse_with_mode(input, ParseMode::Strict).is_err());
+    ///
+    /// let go_mod = GoMod::parse_with_mode(input, ParseMode::Lax).unwrap();
+    ///
+    /// assert_eq!(go_mod.require.len(), 1);
+    /// assert_eq!(go_mod.toolchain, None);
+    /// assert!(go_mod.syntax.to_string().contains("toolchain go1.30.0 with-extras"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, if a parsed directive is invalid.
+    pub fn parse_with_mode(input: &str, mode: ParseMode) -> Result<Self, ParseError> {
+        let state = State::strict(input).with_mode(mode);
+        let mut directives = gomod.parse(Input { input, state })?;
+
+        let mut res = Self {
+            syntax: FileSyntax::from_str(input)?,
+            ..Self::from_directives(&mut directives)
+        };
+        res.attach_comments();
+
+        Ok(res)
+    }
+
+    /// Parses `input` on a best-effort basis.
+    ///
+    /// Unlike [`GoMod::from_str`](std::str::FromStr::from_str), an invalid line
+    /// does not fail the whole file: it is skipped up to the next line, or up
+    /// to the closing `)` for an invalid block, and reported as a diagnostic.
+    /// The returned `GoMod` holds everything that could be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    ///
+    /// let input = r#"
+    /// module github.com/example
+    ///
+    /// require (
+    ///     golang.org/x/net v0.20.0
+    ///     golang.org/x/text
+    /// )
+    /// "#;
+    ///
+    /// let (go_mod, diagnostics) = GoMod::parse_with_diagnostics(input);
+    ///
+    /// assert_eq!(go_mod.require.len(), 1);
+    /// assert_eq!(diagnostics.len(), 1);
+    /// assert_eq!(diagnostics[0].line, 6);
+    /// ```
+    #[must_use]
+    pub fn parse_with_diagnostics(input: &str) -> (Self, Vec<ParseError>) {
+        let diagnostics = RefCell::new(Vec::new());
+        let state = State::recovering(input, &diagnostics);
+
+        let mut res = match gomod.parse(Input { input, state }) {
+            Ok(mut directives) => Self::from_directives(&mut directives),
+            Err(e) => {
+                diagnostics.borrow_mut().push(e.into());
+                Self::default()
+            }
+        };
+
+        let mut diagnostics = diagnostics.into_inner();
+        match FileSyntax::from_str(input) {
+            Ok(syntax) => {
+                res.syntax = syntax;
+                res.attach_comments();
+            }
+            // the syntax tree stays empty, if the file's structure is broken
+            Err(e) => {
+                if !diagnostics.iter().any(|d| d.line == e.line) {
+                    diagnostics.push(e);
+                }
+            }
+        }
+
+        (res, diagnostics)
+    }
+
+    fn from_directives(directives: &mut [Directive]) -> Self {
         let mut res = Self::default();
 
-        for directive in &mut gomod.parse(input).map_err(|e| e.to_string())? {
+        for directive in directives {
             match directive {
                 Directive::Comment(d) => res.comment.push((**d).to_string()),
-                Directive::Module(d) => res.module = (**d).to_string(),
+                Directive::Module(d) => res.module = std::mem::take(d),
                 Directive::Go(d) => res.go = Some((**d).to_string()),
                 Directive::GoDebug(d) => res.godebug.extend((*d).clone()),
                 Directive::Tool(d) => res.tool.append(d),
@@ -75,50 +251,218 @@ impl std::str::FromStr for GoMod {
                 Directive::Replace(d) => res.replace.append(d),
                 Directive::Retract(d) => res.retract.append(d),
                 Directive::Ignore(d) => res.ignore.append(d),
+                Directive::Unknown(d) => res.unknown.push(std::mem::take(d)),
+                // not parsed in go.mod files
+                Directive::Use(_) | Directive::Skipped => {}
             }
         }
 
-        Ok(res)
+        res
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl FromStr for GoMod {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_mode(input, ParseMode::Strict)
+    }
+}
+
+/// How strictly [`GoMod::parse_with_mode`] reads the directives of a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Parses and checks all directives, like Go's `modfile.Parse`. Used for
+    /// the main module.
+    #[default]
+    Strict,
+    /// Parses only the `module`, `go`, `require`, `exclude` and `retract`
+    /// directives, like Go's `modfile.ParseLax`. Used for the `go.mod` files
+    /// of dependencies, which may have been written for a newer Go version.
+    ///
+    /// The other known directives are neither checked nor set in the `GoMod`,
+    /// but kept as raw lines in [`GoMod::syntax`]. Unknown directives are
+    /// kept in [`GoMod::unknown`] in both modes, but only in lax mode
+    /// regardless of the characters of their keyword, like `foo.bar`.
+    Lax,
+}
+
+/// A module path at a version, serialized as
+/// `{"module_path": "golang.org/x/net", "version": "v0.20.0"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Module {
     pub module_path: String,
     pub version: String,
 }
 
+/// The comments of an entry, their text without `//` and surrounding spaces,
+/// serialized as `{"before": ["pinned"], "suffix": null}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comments {
+    /// The comment lines directly above the entry.
+    pub before: Vec<String>,
+    /// The comment at the end of the entry's line.
+    pub suffix: Option<String>,
+}
+
+/// A `require` or `exclude` entry, serialized as
+/// `{"module": {...}, "indirect": false, "comments": {...}}`.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModuleDependency {
     pub module: Module,
     pub indirect: bool,
+    /// The comments of the entry. Of a `// indirect; note` marker, only the
+    /// note is kept as suffix.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub comments: Comments,
 }
 
+/// A `replace` entry, serialized as
+/// `{"module_path": "golang.org/x/net", "version": null, "replacement": {...}, ...}`.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModuleReplacement {
     pub module_path: String,
     pub version: Option<String>,
     pub replacement: Replacement,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub comments: Comments,
 }
 
+/// A `tool` or `ignore` entry, serialized as
+/// `{"path": "./testdata", "comments": {...}}`.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathEntry {
+    pub path: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub comments: Comments,
+}
+
+/// The right-hand side of a `replace` entry, serialized as
+/// `{"file_path": "../net"}` or `{"module": {...}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Replacement {
     FilePath(String),
     Module(Module),
 }
 
+/// A `retract` entry, whose comments are its rationale, serialized as
+/// `{"interval": {"single": "v1.0.0"}, "rationale": "published too early"}`.
 #[derive(Debug, PartialEq, Eq)]
-pub enum ModuleRetract {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleRetract {
+    /// The retracted versions.
+    pub interval: VersionInterval,
+    /// Why the versions are retracted, taken from the comments above or
+    /// beside the entry, or else from those of its block. Comment lines are
+    /// joined by newlines.
+    pub rationale: Option<String>,
+}
+
+/// The versions of a `retract` entry, serialized as `{"single": "v1.0.0"}`
+/// or `{"range": ["v1.0.0", "v1.0.5"]}`.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum VersionInterval {
     Single(String),
     Range(String, String),
 }
 
+/// A directive unknown to this crate, kept to be written back, serialized as
+/// `{"keyword": "frobnicate", "args": ["all"], "block": null}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownDirective {
+    /// The directive keyword, e.g. `frobnicate`.
+    pub keyword: String,
+    /// The unquoted arguments following the keyword, without the `(` of a
+    /// block.
+    pub args: Vec<String>,
+    /// The unquoted arguments of each block line, `None` for a single-line
+    /// directive.
+    pub block: Option<Vec<Vec<String>>>,
+}
+
+impl Module {
+    /// Parses the version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if it is not a valid semantic version.
+    pub fn parse_version(&self) -> Result<Version, ParseError> {
+        self.version.parse()
+    }
+}
+
+impl GoMod {
+    /// Parses the `go` version, `None` without `go` directive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if it is not a valid Go version.
+    pub fn parse_go_version(&self) -> Result<Option<GoVersion>, ParseError> {
+        self.go.as_deref().map(str::parse).transpose()
+    }
+
+    /// The version of the `toolchain`, `None` without `toolchain` directive
+    /// or for a name which is no Go toolchain, see
+    /// [`GoVersion::from_toolchain`].
+    #[must_use]
+    pub fn toolchain_version(&self) -> Option<GoVersion> {
+        self.toolchain
+            .as_deref()
+            .and_then(GoVersion::from_toolchain)
+    }
+}
+
+impl ModuleReplacement {
+    /// Parses the version of the replaced module, `None` if all its versions
+    /// are replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if it is not a valid semantic version.
+    pub fn parse_version(&self) -> Result<Option<Version>, ParseError> {
+        self.version.as_deref().map(str::parse).transpose()
+    }
+}
+
+impl VersionInterval {
+    /// Parses the lowest and highest retracted version, which are equal for a
+    /// single version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a version is not a valid semantic version.
+    pub fn parse_bounds(&self) -> Result<(Version, Version), ParseError> {
+        match self {
+            Self::Single(version) => {
+                let version: Version = version.parse()?;
+                Ok((version.clone(), version))
+            }
+            Self::Range(low, high) => Ok((low.parse()?, high.parse()?)),
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::needless_raw_string_hashes)]
 mod tests {
     use super::*;
     use indoc::indoc;
     use std::str::FromStr;
 
+    fn paths(entries: &[PathEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.path.as_str()).collect()
+    }
+
     #[test]
     fn test_parse_complete() {
         let input = indoc! {r#"
@@ -151,7 +495,8 @@ mod tests {
                     module_path: "golang.org/x/net".to_string(),
                     version: "v0.20.0".to_string()
                 },
-                indirect: false
+                indirect: false,
+                comments: Comments::default(),
             }]
         );
         assert_eq!(
@@ -161,7 +506,8 @@ mod tests {
                     module_path: "golang.org/x/net".to_string(),
                     version: "v0.19.1".to_string()
                 },
-                indirect: false
+                indirect: false,
+                comments: Comments::default(),
             }]
         );
         assert_eq!(
@@ -172,12 +518,16 @@ mod tests {
                 replacement: Replacement::Module(Module {
                     module_path: "example.com/fork/net".to_string(),
                     version: "v0.19.1".to_string(),
-                })
+                }),
+                comments: Comments::default(),
             }]
         );
         assert_eq!(
             go_mod.retract,
-            vec![ModuleRetract::Single("v1.0.0".to_string())]
+            vec![ModuleRetract {
+                interval: VersionInterval::Single("v1.0.0".to_string()),
+                rationale: None,
+            }]
         );
         assert_eq!(go_mod.comment, vec!["Complete example".to_string()]);
     }
@@ -193,6 +543,139 @@ mod tests {
         assert!(go_mod.is_err());
     }
 
+    #[test]
+    fn test_invalid_content_error() {
+        let input = indoc! {r"
+        modulegithub.com/no-space
+        "};
+
+        let err = GoMod::from_str(input).unwrap_err();
+
+        assert_eq!(
+            err,
+            ParseError {
+                offset: 0,
+                line: 1,
+                column: 1,
+                directive: None,
+                expected: vec!["directive".to_string()],
+                found: Some("modulegithub.com/no-space".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_require_error() {
+        let input = indoc! {r"
+        module github.com/invalid-require
+
+        require (
+            golang.org/x/net v0.20.0
+            golang.org/x/text
+        )
+        "};
+
+        let err = GoMod::from_str(input).unwrap_err();
+
+        assert_eq!((err.line, err.column), (5, 22));
+        assert_eq!(err.directive, Some("require block".to_string()));
+        assert_eq!(err.expected, vec!["version".to_string()]);
+        assert_eq!(err.found, Some("\n".to_string()));
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 22: invalid require block, expected version, found `\\n`"
+        );
+    }
+
+    #[test]
+    fn test_invalid_replace_error() {
+        let input = indoc! {r"
+        module github.com/invalid-replace
+
+        replace golang.org/x/net v0.19.0 example.com/fork/net v0.19.1
+        "};
+
+        let err = GoMod::from_str(input).unwrap_err();
+
+        assert_eq!((err.line, err.column), (3, 34));
+        assert_eq!(err.directive, Some("replace arrow".to_string()));
+        assert_eq!(err.expected, vec!["`=>`".to_string()]);
+        assert_eq!(err.found, Some("example.com/fork/net".to_string()));
+    }
+
+    #[test]
+    fn test_parse_with_diagnostics() {
+        let input = indoc! {r"
+        module github.com/diagnostics
+
+        unknown directive
+
+        require (
+            golang.org/x/net v0.20.0
+            golang.org/x/text
+            golang.org/x/sys v0.16.0
+        )
+
+        replace golang.org/x/net v0.19.0 example.com/fork/net v0.19.1
+
+        retract v1.0.0
+        "};
+
+        let (go_mod, diagnostics) = GoMod::parse_with_diagnostics(input);
+
+        assert_eq!(go_mod.module, "github.com/diagnostics".to_string());
+        assert_eq!(
+            go_mod.unknown,
+            vec![UnknownDirective {
+                keyword: "unknown".to_string(),
+                args: vec!["directive".to_string()],
+                block: None,
+            }]
+        );
+        assert_eq!(
+            go_mod
+                .require
+                .iter()
+                .map(|r| r.module.module_path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["golang.org/x/net", "golang.org/x/sys"]
+        );
+        assert!(go_mod.replace.is_empty());
+        assert_eq!(
+            go_mod.retract,
+            vec![ModuleRetract {
+                interval: VersionInterval::Single("v1.0.0".to_string()),
+                rationale: None,
+            }]
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.line, d.directive.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(7, Some("require block")), (11, Some("replace arrow"))]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_diagnostics_skips_invalid_block() {
+        let input = indoc! {r"
+        module github.com/diagnostics
+
+        un.known (
+            golang.org/x/net v0.20.0
+        )
+
+        go 1.21
+        "};
+
+        let (go_mod, diagnostics) = GoMod::parse_with_diagnostics(input);
+
+        assert_eq!(go_mod.go, Some("1.21".to_string()));
+        assert!(go_mod.require.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+    }
+
     #[test]
     fn test_no_line_ending_after_module() {
         let input = indoc! {r#"
@@ -246,8 +729,8 @@ mod tests {
         let go_mod = GoMod::from_str(input).unwrap();
 
         assert_eq!(
-            go_mod.tool,
-            vec!["example.com/mymodule/cmd/mytool1".to_string()]
+            paths(&go_mod.tool),
+            vec!["example.com/mymodule/cmd/mytool1"]
         );
     }
 
@@ -281,7 +764,8 @@ mod tests {
                     module_path: "golang.org/x/net".to_string(),
                     version: "v0.20.0".to_string()
                 },
-                indirect: false
+                indirect: false,
+                comments: Comments::default(),
             }]
         );
     }
@@ -298,7 +782,7 @@ mod tests {
 
         let go_mod = GoMod::from_str(input).unwrap();
 
-        assert_eq!(go_mod.ignore, vec!["./testdata".to_string()]);
+        assert_eq!(paths(&go_mod.ignore), vec!["./testdata"]);
     }
 
     #[test]
@@ -318,12 +802,8 @@ mod tests {
         let go_mod = GoMod::from_str(input).unwrap();
 
         assert_eq!(
-            go_mod.ignore,
-            vec![
-                "./testdata".to_string(),
-                "./vendor/temp".to_string(),
-                "./node_modules".to_string(),
-            ]
+            paths(&go_mod.ignore),
+            vec!["./testdata", "./vendor/temp", "./node_modules"]
         );
     }
 
@@ -340,10 +820,7 @@ mod tests {
 
         let go_mod = GoMod::from_str(input).unwrap();
 
-        assert_eq!(
-            go_mod.ignore,
-            vec!["./testdata".to_string(), "./vendor/temp".to_string()]
-        );
+        assert_eq!(paths(&go_mod.ignore), vec!["./testdata", "./vendor/temp"]);
     }
 
     #[test]
@@ -357,7 +834,7 @@ mod tests {
 
         let go_mod = GoMod::from_str(input).unwrap();
 
-        assert_eq!(go_mod.ignore, vec!["./testdata".to_string()]);
+        assert_eq!(paths(&go_mod.ignore), vec!["./testdata"]);
     }
 
     #[test]
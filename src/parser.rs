@@ -1,83 +1,362 @@
-use crate::combinator::not_whitespace;
-use crate::{Module, ModuleDependency, ModuleReplacement, ModuleRetract, Replacement};
+use crate::combinator::{not_whitespace, string_literal};
+use crate::comment::indirect_note;
+use crate::quote::{token_value, unquote};
+use crate::syntax::{FileSyntax, Line, Stmt};
+use crate::{
+    Comments, Module, ModuleDependency, ModuleReplacement, ModuleRetract, ParseError, ParseMode,
+    PathEntry, Replacement, UnknownDirective, VersionInterval,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
-use winnow::ascii::{multispace0, multispace1, space0, space1};
-use winnow::combinator::{fail, not, opt, peek, preceded, repeat, terminated};
-use winnow::stream::AsChar;
-use winnow::token::{any, take_till, take_while};
-use winnow::{dispatch, Parser, Result};
-
-const WHITESPACES: [char; 4] = [' ', '\t', '\r', '\n'];
+use std::str::FromStr;
+use winnow::ascii::{line_ending, multispace0, multispace1, space0, space1};
+use winnow::combinator::{
+    alt, cut_err, eof, fail, not, opt, peek, preceded, repeat, repeat_till, terminated,
+};
+use winnow::error::{ContextError, ErrMode, StrContext, StrContextValue};
+use winnow::stream::{Offset, Stateful, Stream};
+use winnow::token::{any, one_of, take_till, take_while};
+use winnow::{dispatch, ModalResult, Parser};
+
+pub(crate) const WHITESPACES: [char; 4] = [' ', '\t', '\r', '\n'];
 const CRLF: [char; 2] = ['\r', '\n'];
 
+pub(crate) type Input<'a> = Stateful<&'a str, State<'a>>;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct State<'a> {
+    source: &'a str,
+    diagnostics: Option<&'a RefCell<Vec<ParseError>>>,
+    mode: ParseMode,
+}
+
+impl<'a> State<'a> {
+    /// State which fails on the first invalid line.
+    pub(crate) const fn strict(source: &'a str) -> Self {
+        Self {
+            source,
+            diagnostics: None,
+            mode: ParseMode::Strict,
+        }
+    }
+
+    /// State which collects errors into `diagnostics` and skips invalid lines.
+    pub(crate) const fn recovering(
+        source: &'a str,
+        diagnostics: &'a RefCell<Vec<ParseError>>,
+    ) -> Self {
+        Self {
+            source,
+            diagnostics: Some(diagnostics),
+            mode: ParseMode::Strict,
+        }
+    }
+
+    /// State parsing the directives of a `go.mod` file in `mode`.
+    pub(crate) const fn with_mode(self, mode: ParseMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Records `error`, raised with `rest` left to parse. Returns `false`, if
+    /// no diagnostics are collected and the error has to be propagated instead.
+    pub(crate) fn report(&self, rest: &str, error: &ContextError) -> bool {
+        self.diagnostics.map_or(false, |diagnostics| {
+            let offset = rest.offset_from(&self.source);
+            diagnostics
+                .borrow_mut()
+                .push(ParseError::new(self.source, offset, error));
+            true
+        })
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum Directive<'a> {
     Comment(&'a str),
-    Module(&'a str),
+    Module(String),
     Go(&'a str),
     GoDebug(HashMap<String, String>),
-    Tool(Vec<String>),
+    Tool(Vec<PathEntry>),
     Toolchain(&'a str),
     Require(Vec<ModuleDependency>),
     Exclude(Vec<ModuleDependency>),
     Replace(Vec<ModuleReplacement>),
     Retract(Vec<ModuleRetract>),
-    Ignore(Vec<String>),
+    Ignore(Vec<PathEntry>),
+    Use(Vec<String>),
+    Unknown(UnknownDirective),
+    /// A statement skipped in [`ParseMode::Lax`], kept in the syntax tree only.
+    Skipped,
 }
 
-pub(crate) fn gomod<'a>(input: &mut &'a str) -> Result<Vec<Directive<'a>>> {
-    repeat(0.., |i: &mut &'a str| {
-        // check for comments first
-        comment.parse_next(i).or_else(|_| directive.parse_next(i))
-    })
+/// The directives known in `go.mod` files.
+pub(crate) const DIRECTIVES: [&str; 10] = [
+    "module",
+    "go",
+    "godebug",
+    "tool",
+    "toolchain",
+    "require",
+    "exclude",
+    "replace",
+    "retract",
+    "ignore",
+];
+
+/// The directives parsed in [`ParseMode::Lax`], like Go's `modfile.ParseLax`.
+const LAX_DIRECTIVES: [&str; 5] = ["module", "go", "require", "exclude", "retract"];
+
+pub(crate) const fn expected(description: &'static str) -> StrContext {
+    StrContext::Expected(StrContextValue::Description(description))
+}
+
+pub(crate) fn gomod<'a>(input: &mut Input<'a>) -> ModalResult<Vec<Directive<'a>>> {
+    statements(input, directive)
+}
+
+pub(crate) fn gowork<'a>(input: &mut Input<'a>) -> ModalResult<Vec<Directive<'a>>> {
+    statements(input, work_directive)
+}
+
+/// Parses comments and the directives recognized by `directive` up to the end
+/// of input.
+fn statements<'a>(
+    input: &mut Input<'a>,
+    mut directive: impl Parser<Input<'a>, Directive<'a>, ErrMode<ContextError>>,
+) -> ModalResult<Vec<Directive<'a>>> {
+    let (res, _): (Vec<Option<Directive<'a>>>, _) = repeat_till(
+        0..,
+        |i: &mut Input<'a>| {
+            let start = i.checkpoint();
+            // check for comments first
+            match comment.parse_next(i).or_else(|_| directive.parse_next(i)) {
+                Ok(directive) => Ok(Some(directive)),
+                Err(ErrMode::Backtrack(e) | ErrMode::Cut(e)) if i.state.report(i.input, &e) => {
+                    i.reset(&start);
+                    skip_statement(i)?;
+                    Ok(None)
+                }
+                Err(e) => Err(e),
+            }
+        },
+        (multispace0, eof),
+    )
+    .parse_next(input)?;
+
+    Ok(res.into_iter().flatten().collect())
+}
+
+/// Skips the current line and, if it opens a block, all lines up to the
+/// closing `)`.
+fn skip_statement(input: &mut Input<'_>) -> ModalResult<()> {
+    let line = skip_line(input)?;
+
+    if code(line).trim_end().ends_with('(') {
+        while !input.is_empty() && code(skip_line(input)?).trim() != ")" {}
+    }
+
+    Ok(())
+}
+
+/// The part of `line` before its comment.
+fn code(line: &str) -> &str {
+    line.split("//").next().unwrap_or_default()
+}
+
+/// Skips whitespace and comment lines between the entries of a block.
+fn block_space(input: &mut Input<'_>) -> ModalResult<()> {
+    repeat(0.., alt((multispace1.void(), comment.void()))).parse_next(input)
+}
+
+pub(crate) fn skip_line<'a>(input: &mut Input<'a>) -> ModalResult<&'a str> {
+    terminated(take_till(0.., CRLF), take_while(0.., CRLF)).parse_next(input)
+}
+
+/// Parses an entry of the block named `label`, skipping the rest of its line
+/// instead of failing, if diagnostics are collected.
+fn recover<'a, O: Default>(
+    label: &'static str,
+    mut parser: impl Parser<Input<'a>, O, ErrMode<ContextError>>,
+) -> impl Parser<Input<'a>, O, ErrMode<ContextError>> {
+    move |input: &mut Input<'a>| match parser.parse_next(input) {
+        // at the end of input the block is unterminated, so fail it as a whole
+        Err(ErrMode::Cut(mut e)) if !input.is_empty() && input.state.diagnostics.is_some() => {
+            e.push(StrContext::Label(label));
+            input.state.report(input.input, &e);
+            let _ = take_till(0.., CRLF).parse_next(input)?;
+            Ok(O::default())
+        }
+        res => res,
+    }
+}
+
+fn directive<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let _ = take_while(0.., CRLF).parse_next(input)?;
+    if input.state.mode == ParseMode::Lax {
+        let keyword = peek(take_till(1.., WHITESPACES)).parse_next(input)?;
+        if DIRECTIVES.contains(&keyword) && !LAX_DIRECTIVES.contains(&keyword) {
+            skip_statement(input)?;
+            return Ok(Directive::Skipped);
+        }
+    }
+
+    dispatch!(peek(take_till(1.., WHITESPACES).context(expected("directive")));
+        "module" => cut_err(module.context(StrContext::Label("module"))),
+        "go" => cut_err(go.context(StrContext::Label("go"))),
+        "godebug" => cut_err(godebug),
+        "tool" => cut_err(tool),
+        "toolchain" => cut_err(toolchain.context(StrContext::Label("toolchain"))),
+        "require" => cut_err(require),
+        "exclude" => cut_err(exclude),
+        "replace" => cut_err(replace),
+        "retract" => cut_err(retract),
+        "ignore" => cut_err(ignore),
+        _ => cut_err(unknown_directive),
+    )
     .parse_next(input)
 }
 
-fn directive<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+/// Parses a directive unknown to this crate, of a single line or a block,
+/// into its unquoted tokens. In [`ParseMode::Lax`], any statement is
+/// accepted, and skipped if it cannot be split into tokens.
+fn unknown_directive<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let lax = input.state.mode == ParseMode::Lax;
+    // keywords are words, anything else is likely a typo like `modulefoo/bar`
+    let keyword = peek(take_till(1.., WHITESPACES)).parse_next(input)?;
+    if !lax
+        && (DIRECTIVES.contains(&keyword)
+            || !keyword
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_'))
+    {
+        return fail.context(expected("directive")).parse_next(input);
+    }
+
+    let start = input.checkpoint();
+    let text = skip_statement.take().parse_next(input)?;
+
+    let values = |line: &Line| line.values().map(token_value).collect::<Vec<_>>();
+    let syntax = FileSyntax::from_str(text.trim_end()).unwrap_or_default();
+    let (mut args, block) = match syntax.stmts.as_slice() {
+        [Stmt::Line(line)] => (values(line), None),
+        [Stmt::Block(block)] => {
+            let mut args = values(&block.open);
+            // the `(` opening the block
+            args.pop();
+            let lines = block.lines.iter().filter(|line| !line.tokens.is_empty());
+            (args, Some(lines.map(values).collect()))
+        }
+        _ if lax => return Ok(Directive::Skipped),
+        _ => {
+            input.reset(&start);
+            return fail.context(expected("directive")).parse_next(input);
+        }
+    };
+
+    Ok(Directive::Unknown(UnknownDirective {
+        keyword: args.remove(0),
+        args,
+        block,
+    }))
+}
+
+/// Parses a directive of a `go.work` file.
+fn work_directive<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let _ = take_while(0.., CRLF).parse_next(input)?;
-    dispatch!(peek(not_whitespace);
-        "module" => module,
-        "go" => go,
-        "godebug" => godebug,
-        "tool" => tool,
-        "toolchain" => toolchain,
-        "require" => require,
-        "exclude" => exclude,
-        "replace" => replace,
-        "retract" => retract,
-        "ignore" => ignore,
-        _ => fail,
+    dispatch!(peek(not_whitespace.context(expected("directive")));
+        "go" => cut_err(go.context(StrContext::Label("go"))),
+        "godebug" => cut_err(godebug),
+        "toolchain" => cut_err(toolchain.context(StrContext::Label("toolchain"))),
+        "use" => cut_err(use_),
+        "replace" => cut_err(replace),
+        _ => cut_err(fail.context(expected("directive"))),
     )
     .parse_next(input)
 }
 
-fn comment<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn comment<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded((opt(space0), "//", opt(space0)), take_till(0.., CRLF)).parse_next(input)?;
     let _ = take_while(0.., CRLF).parse_next(input)?;
 
     Ok(Directive::Comment(res))
 }
 
-fn module<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
-    let res = preceded(("module", space1), take_till(1.., CRLF)).parse_next(input)?;
-    let _ = take_while(0.., CRLF).parse_next(input)?;
+fn module<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let res = preceded(
+        ("module", space1.context(expected("whitespace"))),
+        value.context(expected("module path")),
+    )
+    .parse_next(input)?;
+    // a deprecation notice is attached from the syntax tree
+    end_of_line(input)?;
 
     Ok(Directive::Module(res))
 }
 
-fn go<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
-    let res = preceded(("go", space1), take_till(1.., CRLF)).parse_next(input)?;
+/// Skips the rest of a single-value line, which may only hold a comment,
+/// attached from the syntax tree.
+fn end_of_line(input: &mut Input<'_>) -> ModalResult<()> {
+    let _ = (space0, peek(alt((line_ending, eof, "//"))))
+        .context(expected("newline"))
+        .parse_next(input)?;
+    let _ = opt(comment).parse_next(input)?;
     let _ = take_while(0.., CRLF).parse_next(input)?;
 
+    Ok(())
+}
+
+/// Fails unless the entry is followed by nothing but a comment on its line.
+fn end_of_entry(input: &mut Input<'_>) -> ModalResult<()> {
+    let _ = cut_err((space0, peek(alt((line_ending, eof, "//")))))
+        .context(expected("newline"))
+        .parse_next(input)?;
+
+    Ok(())
+}
+
+/// A value, which may be quoted like in Go's `modfile`.
+fn value(input: &mut Input<'_>) -> ModalResult<String> {
+    alt((string, take_till(1.., WHITESPACES).map(String::from))).parse_next(input)
+}
+
+/// A value which is no string literal, like Go requires for versions and
+/// toolchain names.
+fn unquoted<'a>(input: &mut Input<'a>) -> ModalResult<&'a str> {
+    preceded(not(one_of(['"', '`'])), take_till(1.., WHITESPACES)).parse_next(input)
+}
+
+/// An interpreted (`"..."`) or raw (`` `...` ``) string literal, unquoted.
+fn string(input: &mut Input<'_>) -> ModalResult<String> {
+    let start = input.checkpoint();
+    let literal = string_literal.parse_next(input)?;
+
+    if let Some(value) = unquote(literal) {
+        return Ok(value);
+    }
+    input.reset(&start);
+    cut_err(fail)
+        .context(StrContext::Label("string"))
+        .context(expected("valid escape sequence"))
+        .parse_next(input)
+}
+
+fn go<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let res = preceded(
+        ("go", space1.context(expected("whitespace"))),
+        unquoted.context(expected("go version")),
+    )
+    .parse_next(input)?;
+    end_of_line(input)?;
+
     Ok(Directive::Go(res))
 }
 
-fn godebug<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn godebug<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("godebug", space1),
+        ("godebug", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => godebug_multi,
-            _ => godebug_single,
+            '(' => godebug_multi.context(StrContext::Label("godebug block")),
+            _ => godebug_single.context(StrContext::Label("godebug")),
         },
     )
     .parse_next(input)?;
@@ -86,31 +365,39 @@ fn godebug<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::GoDebug(HashMap::from_iter(res)))
 }
 
-fn godebug_single(input: &mut &str) -> Result<Vec<(String, String)>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn godebug_single(input: &mut Input<'_>) -> ModalResult<Vec<(String, String)>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
 
-    let (key, _, value) =
-        (take_till(1.., '='), '=', take_till(1.., WHITESPACES)).parse_next(input)?;
+    let (key, _, value) = cut_err((
+        take_till(1.., |c| c == '=' || WHITESPACES.contains(&c)).context(expected("key")),
+        '='.context(StrContext::Expected('='.into())),
+        take_till(1.., WHITESPACES).context(expected("value")),
+    ))
+    .parse_next(input)?;
+    end_of_entry(input)?;
 
     Ok(vec![(key.into(), value.into())])
 }
 
-fn godebug_multi(input: &mut &str) -> Result<Vec<(String, String)>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<(String, String)>> =
-        repeat(1.., terminated(godebug_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn godebug_multi(input: &mut Input<'_>) -> ModalResult<Vec<(String, String)>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<(String, String)>> = repeat(
+        1..,
+        terminated(recover("godebug block", godebug_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
     Ok(res.into_iter().flatten().collect::<Vec<(String, String)>>())
 }
 
-fn tool<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn tool<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("tool", space1),
+        ("tool", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => tool_multi,
-            _ => tool_single,
+            '(' => tool_multi.context(StrContext::Label("tool block")),
+            _ => tool_single.context(StrContext::Label("tool")),
         },
     )
     .parse_next(input)?;
@@ -119,40 +406,51 @@ fn tool<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Tool(res))
 }
 
-fn tool_single(input: &mut &str) -> Result<Vec<String>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn tool_single(input: &mut Input<'_>) -> ModalResult<Vec<PathEntry>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
 
-    let value = take_till(1.., WHITESPACES).parse_next(input)?;
+    let path = cut_err(value.context(expected("tool path"))).parse_next(input)?;
+    end_of_entry(input)?;
 
-    // remove any comments added to the same line
+    // comments are attached from the syntax tree
     let _ = opt(comment).parse_next(input)?;
 
-    Ok(vec![value.into()])
+    Ok(vec![PathEntry {
+        path,
+        comments: Comments::default(),
+    }])
 }
 
-fn tool_multi(input: &mut &str) -> Result<Vec<String>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<String>> =
-        repeat(1.., terminated(tool_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn tool_multi(input: &mut Input<'_>) -> ModalResult<Vec<PathEntry>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<PathEntry>> = repeat(
+        1..,
+        terminated(recover("tool block", tool_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
-    Ok(res.into_iter().flatten().collect::<Vec<String>>())
+    Ok(res.into_iter().flatten().collect::<Vec<PathEntry>>())
 }
 
-fn toolchain<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
-    let res = preceded(("toolchain", space1), take_till(1.., CRLF)).parse_next(input)?;
-    let _ = take_while(0.., CRLF).parse_next(input)?;
+fn toolchain<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let res = preceded(
+        ("toolchain", space1.context(expected("whitespace"))),
+        unquoted.context(expected("toolchain name")),
+    )
+    .parse_next(input)?;
+    end_of_line(input)?;
 
     Ok(Directive::Toolchain(res))
 }
 
-fn require<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn require<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("require", space1),
+        ("require", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => require_multi,
-            _ => require_single,
+            '(' => require_multi.context(StrContext::Label("require block")),
+            _ => require_single.context(StrContext::Label("require")),
         },
     )
     .parse_next(input)?;
@@ -161,43 +459,50 @@ fn require<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Require(res))
 }
 
-fn require_single(input: &mut &str) -> Result<Vec<ModuleDependency>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn require_single(input: &mut Input<'_>) -> ModalResult<Vec<ModuleDependency>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
 
-    let (module_path, _, version) = (
-        take_till(1.., AsChar::is_space),
-        space1,
-        take_till(1.., WHITESPACES),
-    )
-        .parse_next(input)?;
+    let (module_path, version) = cut_err((
+        value.context(expected("module path")),
+        preceded(space1, value).context(expected("version")),
+    ))
+    .parse_next(input)?;
+    end_of_entry(input)?;
 
-    let indirect = opt(comment).parse_next(input)? == Some(Directive::Comment("indirect"));
+    let indirect = matches!(
+        opt(comment).parse_next(input)?,
+        Some(Directive::Comment(text)) if indirect_note(text).is_some()
+    );
 
     Ok(vec![ModuleDependency {
         module: Module {
-            module_path: module_path.to_string(),
-            version: version.to_string(),
+            module_path,
+            version,
         },
         indirect,
+        comments: Comments::default(),
     }])
 }
 
-fn require_multi(input: &mut &str) -> Result<Vec<ModuleDependency>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<ModuleDependency>> =
-        repeat(1.., terminated(require_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn require_multi(input: &mut Input<'_>) -> ModalResult<Vec<ModuleDependency>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<ModuleDependency>> = repeat(
+        1..,
+        terminated(recover("require block", require_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
     Ok(res.into_iter().flatten().collect::<Vec<ModuleDependency>>())
 }
 
-fn exclude<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn exclude<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("exclude", space1),
+        ("exclude", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => require_multi,
-            _ => require_single,
+            '(' => exclude_multi.context(StrContext::Label("exclude block")),
+            _ => require_single.context(StrContext::Label("exclude")),
         },
     )
     .parse_next(input)?;
@@ -206,12 +511,24 @@ fn exclude<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Exclude(res))
 }
 
-fn replace<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn exclude_multi(input: &mut Input<'_>) -> ModalResult<Vec<ModuleDependency>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<ModuleDependency>> = repeat(
+        1..,
+        terminated(recover("exclude block", require_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
+
+    Ok(res.into_iter().flatten().collect::<Vec<ModuleDependency>>())
+}
+
+fn replace<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("replace", space1),
+        ("replace", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => replace_multi,
-            _ => replace_single,
+            '(' => replace_multi.context(StrContext::Label("replace block")),
+            _ => replace_single.context(StrContext::Label("replace")),
         },
     )
     .parse_next(input)?;
@@ -220,47 +537,51 @@ fn replace<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Replace(res))
 }
 
-fn replace_single(input: &mut &str) -> Result<Vec<ModuleReplacement>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn replace_single(input: &mut Input<'_>) -> ModalResult<Vec<ModuleReplacement>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
+
+    let (src_path, src_version, _, (dest_path, dest_version)) = cut_err((
+        terminated(value, space1).context(expected("module path")),
+        opt(terminated(preceded(peek(not("=>")), value), space1)),
+        ("=>", space1)
+            .context(StrContext::Label("replace arrow"))
+            .context(StrContext::Expected("=>".into())),
+        (
+            terminated(value.context(expected("replacement path")), space0),
+            opt(terminated(preceded(peek(not("//")), value), space0)),
+        ),
+    ))
+    .parse_next(input)?;
+    end_of_entry(input)?;
 
-    let (src_path, src_version) = (
-        terminated(take_till(1.., AsChar::is_space), space1),
-        opt(terminated(
-            preceded(peek(not("=>")), take_till(1.., AsChar::is_space)),
-            space1,
-        )),
-    )
-        .parse_next(input)?;
-    let _ = ("=>", space1).parse_next(input)?;
-    let (dest_path, dest_version) = (
-        terminated(take_till(1.., WHITESPACES), space0),
-        opt(terminated(take_till(1.., WHITESPACES), multispace1)),
-    )
-        .parse_next(input)?;
+    // comments are attached from the syntax tree
+    let _ = opt(comment).parse_next(input)?;
 
-    let replacement = dest_version.map_or_else(
-        || Replacement::FilePath(dest_path.to_string()),
-        |version| {
-            Replacement::Module(Module {
-                module_path: dest_path.to_string(),
-                version: version.to_string(),
-            })
-        },
-    );
+    let replacement = match dest_version {
+        Some(version) => Replacement::Module(Module {
+            module_path: dest_path,
+            version,
+        }),
+        None => Replacement::FilePath(dest_path),
+    };
 
     Ok(vec![ModuleReplacement {
-        module_path: src_path.to_string(),
-        version: src_version.map(ToString::to_string),
+        module_path: src_path,
+        version: src_version,
         replacement,
+        comments: Comments::default(),
     }])
 }
 
-fn replace_multi(input: &mut &str) -> Result<Vec<ModuleReplacement>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<ModuleReplacement>> =
-        repeat(1.., terminated(replace_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn replace_multi(input: &mut Input<'_>) -> ModalResult<Vec<ModuleReplacement>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<ModuleReplacement>> = repeat(
+        1..,
+        terminated(recover("replace block", replace_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
     Ok(res
         .into_iter()
@@ -268,12 +589,12 @@ fn replace_multi(input: &mut &str) -> Result<Vec<ModuleReplacement>> {
         .collect::<Vec<ModuleReplacement>>())
 }
 
-fn retract<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn retract<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("retract", space1),
+        ("retract", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => retract_multi,
-            _ => retract_single,
+            '(' => retract_multi.context(StrContext::Label("retract block")),
+            _ => retract_single.context(StrContext::Label("retract")),
         },
     )
     .parse_next(input)?;
@@ -282,55 +603,76 @@ fn retract<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Retract(res))
 }
 
-fn retract_single(input: &mut &str) -> Result<Vec<ModuleRetract>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn retract_single(input: &mut Input<'_>) -> ModalResult<Vec<ModuleRetract>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
 
-    let res = dispatch! {peek(any);
-        '[' => version_range,
+    let interval = cut_err(dispatch! {peek(any);
+        '[' => version_range.context(StrContext::Label("retract range")),
         _ => version_single,
-    }
+    })
     .parse_next(input)?;
+    end_of_entry(input)?;
 
-    // remove any comments added to the same line
+    // the rationale is attached from the syntax tree
     let _ = opt(comment).parse_next(input)?;
 
-    Ok(vec![res])
+    Ok(vec![ModuleRetract {
+        interval,
+        rationale: None,
+    }])
 }
 
-fn version_range(input: &mut &str) -> Result<ModuleRetract> {
-    let lower_bound = preceded('[', take_till(1.., |c| c == ',' || c == ' ')).parse_next(input)?;
-    let _ = (',', space0).parse_next(input)?;
-    let upper_bound =
-        terminated(take_till(1.., |c| c == ']' || c == ' '), ']').parse_next(input)?;
+fn version_range(input: &mut Input<'_>) -> ModalResult<VersionInterval> {
+    let lower_bound = preceded(
+        '[',
+        alt((
+            string,
+            take_till(1.., |c| c == ',' || WHITESPACES.contains(&c)).map(String::from),
+        ))
+        .context(expected("lower bound")),
+    )
+    .parse_next(input)?;
+    let _ = (',', space0)
+        .context(StrContext::Expected(','.into()))
+        .parse_next(input)?;
+    let upper_bound = terminated(
+        alt((
+            string,
+            take_till(1.., |c| c == ']' || WHITESPACES.contains(&c)).map(String::from),
+        ))
+        .context(expected("upper bound")),
+        ']'.context(StrContext::Expected(']'.into())),
+    )
+    .parse_next(input)?;
 
-    Ok(ModuleRetract::Range(
-        lower_bound.to_string(),
-        upper_bound.to_string(),
-    ))
+    Ok(VersionInterval::Range(lower_bound, upper_bound))
 }
 
-fn version_single(input: &mut &str) -> Result<ModuleRetract> {
-    let version = terminated(take_till(1.., WHITESPACES), multispace1).parse_next(input)?;
+fn version_single(input: &mut Input<'_>) -> ModalResult<VersionInterval> {
+    let version = value.context(expected("version")).parse_next(input)?;
 
-    Ok(ModuleRetract::Single(version.to_string()))
+    Ok(VersionInterval::Single(version))
 }
 
-fn retract_multi(input: &mut &str) -> Result<Vec<ModuleRetract>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<ModuleRetract>> =
-        repeat(1.., terminated(retract_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn retract_multi(input: &mut Input<'_>) -> ModalResult<Vec<ModuleRetract>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<ModuleRetract>> = repeat(
+        1..,
+        terminated(recover("retract block", retract_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
     Ok(res.into_iter().flatten().collect::<Vec<ModuleRetract>>())
 }
 
-fn ignore<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
+fn ignore<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
     let res = preceded(
-        ("ignore", space1),
+        ("ignore", space1.context(expected("whitespace"))),
         dispatch! {peek(any);
-            '(' => ignore_multi,
-            _ => ignore_single,
+            '(' => ignore_multi.context(StrContext::Label("ignore block")),
+            _ => ignore_single.context(StrContext::Label("ignore")),
         },
     )
     .parse_next(input)?;
@@ -339,23 +681,71 @@ fn ignore<'a>(input: &mut &'a str) -> Result<Directive<'a>> {
     Ok(Directive::Ignore(res))
 }
 
-fn ignore_single(input: &mut &str) -> Result<Vec<String>> {
-    // terminate, if `)` is found
-    peek(not(')')).parse_next(input)?;
+fn ignore_single(input: &mut Input<'_>) -> ModalResult<Vec<PathEntry>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
+
+    let path = cut_err(value.context(expected("path"))).parse_next(input)?;
+    end_of_entry(input)?;
+
+    // comments are attached from the syntax tree
+    let _ = opt(comment).parse_next(input)?;
+
+    Ok(vec![PathEntry {
+        path,
+        comments: Comments::default(),
+    }])
+}
+
+fn ignore_multi(input: &mut Input<'_>) -> ModalResult<Vec<PathEntry>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<PathEntry>> = repeat(
+        1..,
+        terminated(recover("ignore block", ignore_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
+
+    Ok(res.into_iter().flatten().collect::<Vec<PathEntry>>())
+}
+
+fn use_<'a>(input: &mut Input<'a>) -> ModalResult<Directive<'a>> {
+    let res = preceded(
+        ("use", space1.context(expected("whitespace"))),
+        dispatch! {peek(any);
+            '(' => use_multi.context(StrContext::Label("use block")),
+            _ => use_single.context(StrContext::Label("use")),
+        },
+    )
+    .parse_next(input)?;
+    let _ = take_while(0.., CRLF).parse_next(input)?;
+
+    Ok(Directive::Use(res))
+}
+
+fn use_single(input: &mut Input<'_>) -> ModalResult<Vec<String>> {
+    // terminate, if `)` or the end of input is found
+    peek(not(alt((")", eof)))).parse_next(input)?;
 
-    let path = take_till(1.., WHITESPACES).parse_next(input)?;
+    let path = cut_err(value.context(expected("path"))).parse_next(input)?;
+    let _ = cut_err((space0, peek(alt((line_ending, eof, "//")))))
+        .context(expected("newline"))
+        .parse_next(input)?;
 
     // remove any comments added to the same line
     let _ = opt(comment).parse_next(input)?;
 
-    Ok(vec![path.to_string()])
+    Ok(vec![path])
 }
 
-fn ignore_multi(input: &mut &str) -> Result<Vec<String>> {
-    let _ = ("(", multispace1).parse_next(input)?;
-    let res: Vec<Vec<String>> =
-        repeat(1.., terminated(ignore_single, multispace0)).parse_next(input)?;
-    let _ = (")", multispace0).parse_next(input)?;
+fn use_multi(input: &mut Input<'_>) -> ModalResult<Vec<String>> {
+    let _ = ("(", multispace1.context(expected("newline")), block_space).parse_next(input)?;
+    let res: Vec<Vec<String>> = repeat(
+        1..,
+        terminated(recover("use block", use_single), block_space),
+    )
+    .parse_next(input)?;
+    let _ = (")".context(StrContext::Expected(')'.into())), multispace0).parse_next(input)?;
 
     Ok(res.into_iter().flatten().collect::<Vec<String>>())
 }
@@ -0,0 +1,573 @@
+//! Validation of module and package paths and their major version suffixes,
+//! and their escaping for proxies and module caches, following the rules of
+//! Go's `golang.org/x/mod/module` package.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gomod_parser::path::{check_major_version, check_path};
+//!
+//! assert!(check_path("github.com/example/project/v2").is_ok());
+//!
+//! let err = check_path("example/project").unwrap_err();
+//! assert_eq!(err.reason, "missing dot in first path element".to_string());
+//!
+//! let err = check_major_version("github.com/example/project", "v2.0.0").unwrap_err();
+//! assert_eq!(err.reason, "should be v0 or v1, not v2".to_string());
+//! ```
+
+use crate::{GoMod, Module, Replacement, Version};
+use std::fmt;
+
+/// Path elements reserved on Windows, regardless of their extension.
+const BAD_WINDOWS_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// An error describing why a path, or the version of a module at that path,
+/// is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathError {
+    /// The directive of the entry holding the path, if checked as part of a
+    /// [`GoMod`], e.g. `require`.
+    pub directive: Option<String>,
+    /// The invalid path.
+    pub path: String,
+    /// The invalid version, if the version rather than the path is invalid.
+    pub version: Option<String>,
+    /// Why the path is invalid, worded like the Go toolchain.
+    pub reason: String,
+}
+
+impl PathError {
+    fn new(path: &str, reason: impl Into<String>) -> Self {
+        Self {
+            directive: None,
+            path: path.to_string(),
+            version: None,
+            reason: reason.into(),
+        }
+    }
+
+    fn version(path: &str, version: &str, reason: impl Into<String>) -> Self {
+        Self {
+            version: Some(version.to_string()),
+            ..Self::new(path, reason)
+        }
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(directive) = &self.directive {
+            write!(f, "{directive}: ")?;
+        }
+        match &self.version {
+            Some(version) => write!(
+                f,
+                "{}@{version}: invalid version: {}",
+                self.path, self.reason
+            ),
+            None => write!(f, "malformed path {:?}: {}", self.path, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PathKind {
+    Module,
+    Import,
+    File,
+}
+
+/// Checks that `path` is a valid module path, like Go's `module.CheckPath`.
+///
+/// Module paths consist of `/`-separated elements of ASCII letters, digits
+/// and `-._~`. Their first element is a host name: it must contain a dot and
+/// only lowercase letters, digits, `-` and `.`. A major version suffix must
+/// be well-formed, see [`split_path_version`].
+///
+/// # Errors
+///
+/// Returns an error describing the first violated rule.
+pub fn check_path(path: &str) -> Result<(), PathError> {
+    check(path, PathKind::Module)?;
+
+    let first = path.split('/').next().unwrap_or_default();
+    if first.is_empty() {
+        return Err(PathError::new(path, "leading slash"));
+    }
+    if !first.contains('.') {
+        return Err(PathError::new(path, "missing dot in first path element"));
+    }
+    if let Some(c) = first
+        .chars()
+        .find(|&c| !(c == '-' || c == '.' || c.is_ascii_digit() || c.is_ascii_lowercase()))
+    {
+        return Err(PathError::new(
+            path,
+            format!("invalid char {c:?} in first path element"),
+        ));
+    }
+    if split_path_version(path).is_none() {
+        return Err(PathError::new(path, "invalid version"));
+    }
+
+    Ok(())
+}
+
+/// Checks that `path` is a valid package import path, like Go's
+/// `module.CheckImportPath`.
+///
+/// Unlike module paths, import paths need no dot in their first element, may
+/// contain `+` and elements may start with a dot.
+///
+/// # Errors
+///
+/// Returns an error describing the first violated rule.
+pub fn check_import_path(path: &str) -> Result<(), PathError> {
+    check(path, PathKind::Import)
+}
+
+/// Splits `path` into its prefix and major version suffix, like Go's
+/// `module.SplitPathVersion`.
+///
+/// For example, `github.com/example/v2` is split into `github.com/example`
+/// and `/v2`, and `gopkg.in/yaml.v3` into `gopkg.in/yaml` and `.v3`. The
+/// suffix is empty for paths without one. Returns `None` for malformed
+/// suffixes like `/v1`, `/v0` or `/v2.1`, and for `gopkg.in` paths without
+/// `.vN` suffix.
+#[must_use]
+pub fn split_path_version(path: &str) -> Option<(&str, &str)> {
+    if path.starts_with("gopkg.in/") {
+        return split_gopkg_in(path);
+    }
+
+    let bytes = path.as_bytes();
+    let mut i = bytes.len();
+    let mut dot = false;
+    while i > 0 && (bytes[i - 1].is_ascii_digit() || bytes[i - 1] == b'.') {
+        dot |= bytes[i - 1] == b'.';
+        i -= 1;
+    }
+    if i <= 1 || i == bytes.len() || bytes[i - 1] != b'v' || bytes[i - 2] != b'/' {
+        return Some((path, ""));
+    }
+
+    let (prefix, major) = path.split_at(i - 2);
+    if dot || major.len() <= 2 || major.as_bytes()[2] == b'0' || major == "/v1" {
+        return None;
+    }
+
+    Some((prefix, major))
+}
+
+fn split_gopkg_in(path: &str) -> Option<(&str, &str)> {
+    let end = path.strip_suffix("-unstable").unwrap_or(path).len();
+    let bytes = path.as_bytes();
+    let mut i = end;
+    while i > 0 && bytes[i - 1].is_ascii_digit() {
+        i -= 1;
+    }
+    if i <= 1 || i == end || bytes[i - 1] != b'v' || bytes[i - 2] != b'.' {
+        // all gopkg.in paths must end in `.vN`
+        return None;
+    }
+
+    let (prefix, major) = path.split_at(i - 2);
+    if major.as_bytes()[2] == b'0' && major != ".v0" {
+        return None;
+    }
+
+    Some((prefix, major))
+}
+
+/// Checks that `version` matches the major version suffix of the module
+/// `path`, like Go's `module.CheckPathMajor`.
+///
+/// Versions `v2` and above need a matching `/vN` suffix, or `.vN` for
+/// `gopkg.in` paths, unless they are `+incompatible`. Versions of paths
+/// without suffix must be `v0` or `v1`. `+incompatible` is only allowed on
+/// `v2` and above for paths without suffix.
+///
+/// # Errors
+///
+/// Returns an error if the suffix is malformed, like `/v1`, or does not
+/// match `version`, or if `version` is not a valid semantic version.
+pub fn check_major_version(path: &str, version: &str) -> Result<(), PathError> {
+    let Some((_, path_major)) = split_path_version(path) else {
+        return Err(PathError::new(path, "invalid version"));
+    };
+    let Ok(parsed) = version.parse::<Version>() else {
+        return Err(PathError::version(path, version, "not a semantic version"));
+    };
+
+    // gopkg.in allows any version with `-unstable` suffixes, and old
+    // toolchains created `v0.0.0-` pseudo-versions for `.v1` paths
+    if path_major.starts_with(".v") && path_major.ends_with("-unstable")
+        || path_major == ".v1" && version.starts_with("v0.0.0-")
+    {
+        return Ok(());
+    }
+
+    if parsed.is_incompatible() {
+        return if !path_major.is_empty() {
+            Err(PathError::version(
+                path,
+                version,
+                "+incompatible not allowed with major version suffix",
+            ))
+        } else if parsed.major < 2 {
+            Err(PathError::version(
+                path,
+                version,
+                format!("+incompatible not allowed on v{}", parsed.major),
+            ))
+        } else {
+            Ok(())
+        };
+    }
+
+    let major = format!("v{}", parsed.major);
+    if path_major.is_empty() {
+        if parsed.major < 2 {
+            return Ok(());
+        }
+        return Err(PathError::version(
+            path,
+            version,
+            format!("should be v0 or v1, not {major}"),
+        ));
+    }
+    if path_major[1..] != major {
+        return Err(PathError::version(
+            path,
+            version,
+            format!("should be {}, not {major}", &path_major[1..]),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check(path: &str, kind: PathKind) -> Result<(), PathError> {
+    if path.is_empty() {
+        return Err(PathError::new(path, "empty string"));
+    }
+    if path.starts_with('-') {
+        return Err(PathError::new(path, "leading dash"));
+    }
+    if path.contains("//") {
+        return Err(PathError::new(path, "double slash"));
+    }
+    if path.ends_with('/') {
+        return Err(PathError::new(path, "trailing slash"));
+    }
+
+    path.split('/')
+        .try_for_each(|elem| check_elem(elem, kind).map_err(|reason| PathError::new(path, reason)))
+}
+
+fn check_elem(elem: &str, kind: PathKind) -> Result<(), String> {
+    if elem.is_empty() {
+        return Err("empty path element".to_string());
+    }
+    if elem.chars().all(|c| c == '.') {
+        return Err(format!("invalid path element {elem:?}"));
+    }
+    if elem.starts_with('.') && kind == PathKind::Module {
+        return Err("leading dot in path element".to_string());
+    }
+    if elem.ends_with('.') {
+        return Err("trailing dot in path element".to_string());
+    }
+    if let Some(c) = elem.chars().find(|&c| !is_allowed_char(c, kind)) {
+        return Err(format!("invalid char {c:?}"));
+    }
+
+    let short = elem.split('.').next().unwrap_or_default();
+    if BAD_WINDOWS_NAMES
+        .iter()
+        .any(|bad| bad.eq_ignore_ascii_case(short))
+    {
+        return Err(format!(
+            "{short:?} disallowed as path element component on Windows"
+        ));
+    }
+    if kind == PathKind::File {
+        return Ok(());
+    }
+    // reject elements looking like Windows short names, e.g. `PROGRA~1`
+    if let Some((_, suffix)) = short.rsplit_once('~') {
+        if !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()) {
+            return Err("trailing tilde and digits in path element".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn is_allowed_char(c: char, kind: PathKind) -> bool {
+    match kind {
+        PathKind::Module => c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'),
+        PathKind::Import => c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '+'),
+        PathKind::File => {
+            c.is_alphabetic() || c.is_ascii_digit() || "!#$%&()+,-.=@[]^_{}~ ".contains(c)
+        }
+    }
+}
+
+/// Replaces uppercase letters by `!` and their lowercase, which keeps paths
+/// distinct on case-insensitive file systems.
+fn escape(s: &str) -> Option<String> {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '!' || !c.is_ascii() {
+            return None;
+        }
+        if c.is_ascii_uppercase() {
+            res.push('!');
+        }
+        res.push(c.to_ascii_lowercase());
+    }
+    Some(res)
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '!' => res.push(
+                chars
+                    .next()
+                    .filter(char::is_ascii_lowercase)?
+                    .to_ascii_uppercase(),
+            ),
+            _ if c.is_ascii_uppercase() => return None,
+            _ => res.push(c),
+        }
+    }
+    Some(res)
+}
+
+impl Module {
+    /// Splits the module path into its prefix and major version suffix, see
+    /// [`split_path_version`].
+    #[must_use]
+    pub fn split_path(&self) -> Option<(&str, &str)> {
+        split_path_version(&self.module_path)
+    }
+
+    /// Checks that the version matches the major version suffix of the module
+    /// path, see [`check_major_version`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if they do not match.
+    pub fn check_major_version(&self) -> Result<(), PathError> {
+        check_major_version(&self.module_path, &self.version)
+    }
+
+    /// Escapes the module path for use in `GOPROXY` URLs and module cache
+    /// directories, like Go's `module.EscapePath`: each uppercase letter is
+    /// replaced by `!` and its lowercase.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::Module;
+    ///
+    /// let module = Module {
+    ///     module_path: "github.com/Azure/azure-sdk-for-go".to_string(),
+    ///     version: "v68.0.0+incompatible".to_string(),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     module.escape_path().unwrap(),
+    ///     "github.com/!azure/azure-sdk-for-go".to_string()
+    /// );
+    /// assert_eq!(
+    ///     Module::unescape_path("github.com/!azure/azure-sdk-for-go").unwrap(),
+    ///     module.module_path
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module path is invalid, see [`check_path`].
+    pub fn escape_path(&self) -> Result<String, PathError> {
+        check_path(&self.module_path)?;
+        escape(&self.module_path).ok_or_else(|| PathError::new(&self.module_path, "invalid char"))
+    }
+
+    /// Escapes the version for use in `GOPROXY` URLs and module cache
+    /// directories, like Go's `module.EscapeVersion`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not allowed as file name or
+    /// contains a `!`.
+    pub fn escape_version(&self) -> Result<String, PathError> {
+        let disallowed = || {
+            PathError::version(
+                &self.module_path,
+                &self.version,
+                "disallowed version string",
+            )
+        };
+
+        if self.version.contains('!') || check_elem(&self.version, PathKind::File).is_err() {
+            return Err(disallowed());
+        }
+        escape(&self.version).ok_or_else(disallowed)
+    }
+
+    /// Unescapes a module path escaped by [`Module::escape_path`], like Go's
+    /// `module.UnescapePath`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `escaped` has uppercase letters, a `!` not
+    /// followed by a lowercase letter, or is no valid module path once
+    /// unescaped.
+    pub fn unescape_path(escaped: &str) -> Result<String, PathError> {
+        let path = unescape(escaped)
+            .ok_or_else(|| PathError::new(escaped, "invalid escaped module path"))?;
+        check_path(&path)?;
+        Ok(path)
+    }
+}
+
+impl GoMod {
+    /// Checks the paths of all entries, returning an error for each invalid
+    /// one.
+    ///
+    /// The `module` path and the `tool` packages are checked as import paths,
+    /// as the main module needs no host name, the paths of the `require`,
+    /// `exclude` and `replace` modules as module paths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example
+    ///
+    /// require (
+    ///     golang.org/x/net v0.20.0
+    ///     golang.org/x/Text! v0.14.0
+    /// )
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    /// let errors = go_mod.check_paths();
+    ///
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].to_string(), r#"require: malformed path "golang.org/x/Text!": invalid char '!'"#);
+    /// ```
+    #[must_use]
+    pub fn check_paths(&self) -> Vec<PathError> {
+        // the directive, the path and whether it is a module path
+        let mut paths: Vec<(&str, &str, bool)> = Vec::new();
+
+        if !self.module.is_empty() {
+            paths.push(("module", &self.module, false));
+        }
+        for dependency in &self.require {
+            paths.push(("require", &dependency.module.module_path, true));
+        }
+        for dependency in &self.exclude {
+            paths.push(("exclude", &dependency.module.module_path, true));
+        }
+        for replacement in &self.replace {
+            paths.push(("replace", &replacement.module_path, true));
+            if let Replacement::Module(module) = &replacement.replacement {
+                paths.push(("replace", &module.module_path, true));
+            }
+        }
+        for tool in &self.tool {
+            paths.push(("tool", &tool.path, false));
+        }
+
+        paths
+            .into_iter()
+            .filter_map(|(directive, path, module)| {
+                let res = if module {
+                    check_path(path)
+                } else {
+                    check_import_path(path)
+                };
+                res.err().map(|e| PathError {
+                    directive: Some(directive.to_string()),
+                    ..e
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that the versions of all `require`, `exclude` and `replace`
+    /// entries match the major version suffixes of their module paths,
+    /// returning an error for each mismatch, see [`check_major_version`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::GoMod;
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example
+    ///
+    /// require (
+    ///     github.com/example/a/v2 v2.1.0
+    ///     github.com/example/b v2.0.0
+    /// )
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    /// let errors = go_mod.check_major_versions();
+    ///
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(
+    ///     errors[0].to_string(),
+    ///     "require: github.com/example/b@v2.0.0: invalid version: should be v0 or v1, not v2"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn check_major_versions(&self) -> Vec<PathError> {
+        let mut modules: Vec<(&str, &str, &str)> = Vec::new();
+
+        for dependency in &self.require {
+            let module = &dependency.module;
+            modules.push(("require", &module.module_path, &module.version));
+        }
+        for dependency in &self.exclude {
+            let module = &dependency.module;
+            modules.push(("exclude", &module.module_path, &module.version));
+        }
+        for replacement in &self.replace {
+            if let Some(version) = &replacement.version {
+                modules.push(("replace", &replacement.module_path, version));
+            }
+            if let Replacement::Module(module) = &replacement.replacement {
+                modules.push(("replace", &module.module_path, &module.version));
+            }
+        }
+
+        modules
+            .into_iter()
+            .filter_map(|(directive, path, version)| {
+                check_major_version(path, version).err().map(|e| PathError {
+                    directive: Some(directive.to_string()),
+                    ..e
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,203 @@
+use crate::Version;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The parts of a pseudo-version, which refers to an untagged revision.
+///
+/// Go writes pseudo-versions in three forms, depending on the latest tag
+/// before the revision:
+///
+/// - `vX.0.0-yyyymmddhhmmss-abcdefabcdef` without earlier tag
+/// - `vX.Y.Z-pre.0.yyyymmddhhmmss-abcdefabcdef` after the prerelease tag
+///   `vX.Y.Z-pre`
+/// - `vX.Y.(Z+1)-0.yyyymmddhhmmss-abcdefabcdef` after the release tag
+///   `vX.Y.Z`
+///
+/// # Example
+///
+/// ```rust
+/// use gomod_parser::Version;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let version: Version = "v1.2.4-0.20240102150405-abcdef123456".parse().unwrap();
+/// let pseudo = version.decode_pseudo().unwrap();
+///
+/// assert_eq!(pseudo.base, Some("v1.2.3".parse().unwrap()));
+/// assert_eq!(pseudo.time, UNIX_EPOCH + Duration::from_secs(1_704_207_845));
+/// assert_eq!(pseudo.revision, "abcdef123456".to_string());
+///
+/// assert_eq!(
+///     Version::pseudo(1, pseudo.base.as_ref(), pseudo.time, &pseudo.revision),
+///     version
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PseudoVersion {
+    /// The tag the pseudo-version is based on, `None` for the first form.
+    pub base: Option<Version>,
+    /// The UTC commit time, in seconds.
+    pub time: SystemTime,
+    /// The revision identifier, usually a 12 character commit hash prefix.
+    pub revision: String,
+}
+
+impl Version {
+    /// Builds the pseudo-version of `revision` committed at `time`, following
+    /// the latest tag `base`, or with the `major` version if there is none.
+    ///
+    /// A full 40 character commit hash is shortened to its first 12
+    /// characters. Build metadata of `base`, like `+incompatible`, is kept.
+    #[must_use]
+    pub fn pseudo(major: u64, base: Option<&Self>, time: SystemTime, revision: &str) -> Self {
+        let revision = if revision.len() == 40 && revision.bytes().all(|b| b.is_ascii_hexdigit()) {
+            &revision[..12]
+        } else {
+            revision
+        };
+        let segment = format!("{}-{revision}", timestamp(time));
+
+        let Some(base) = base else {
+            return Self {
+                major,
+                minor: 0,
+                patch: 0,
+                prerelease: Some(segment),
+                build: None,
+            };
+        };
+
+        let mut res = base.clone();
+        if let Some(prerelease) = &mut res.prerelease {
+            *prerelease = format!("{prerelease}.0.{segment}");
+        } else {
+            res.patch += 1;
+            res.prerelease = Some(format!("0.{segment}"));
+        }
+        res
+    }
+
+    /// Returns `true` for pseudo-versions, see [`PseudoVersion`].
+    #[must_use]
+    pub fn is_pseudo(&self) -> bool {
+        self.decode_pseudo().is_some()
+    }
+
+    /// Decodes a pseudo-version into its base tag, commit time and revision.
+    /// Returns `None` if this is not a valid pseudo-version.
+    #[must_use]
+    pub fn decode_pseudo(&self) -> Option<PseudoVersion> {
+        let prerelease = self.prerelease.as_deref()?;
+        let (rest, revision) = prerelease.rsplit_once('-')?;
+        if revision.is_empty() || !revision.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+
+        let (prefix, digits) = rest.split_at(rest.len().checked_sub(14)?);
+        let time = parse_timestamp(digits)?;
+
+        let base = match prefix {
+            // vX.0.0-yyyymmddhhmmss-abcdefabcdef
+            "" if self.minor == 0 && self.patch == 0 => None,
+            // vX.Y.(Z+1)-0.yyyymmddhhmmss-abcdefabcdef
+            "0." => Some(Self {
+                patch: self.patch.checked_sub(1)?,
+                prerelease: None,
+                ..self.clone()
+            }),
+            // vX.Y.Z-pre.0.yyyymmddhhmmss-abcdefabcdef
+            _ => Some(Self {
+                prerelease: Some(
+                    prefix
+                        .strip_suffix(".0.")
+                        .filter(|p| !p.is_empty())?
+                        .to_string(),
+                ),
+                ..self.clone()
+            }),
+        };
+
+        Some(PseudoVersion {
+            base,
+            time,
+            revision: revision.to_string(),
+        })
+    }
+}
+
+/// Formats `time` as UTC `yyyymmddhhmmss`.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}{month:02}{day:02}{:02}{:02}{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Parses a UTC `yyyymmddhhmmss` timestamp.
+fn parse_timestamp(digits: &str) -> Option<SystemTime> {
+    if digits.len() != 14 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| digits[range].parse::<u64>().ok();
+
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if year < 1970
+        || !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+const fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a date on or after it, see
+/// <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+const fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date of a number of days since 1970-01-01, see
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
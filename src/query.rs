@@ -0,0 +1,86 @@
+use crate::{GoMod, Module, ModuleRetract, Version, VersionInterval};
+use std::cmp::Ordering;
+
+impl GoMod {
+    /// Returns `true` if `module` is listed in an `exclude` entry.
+    ///
+    /// Versions are compared as semantic versions, so `v1.2` matches an
+    /// exclusion of `v1.2.0`.
+    #[must_use]
+    pub fn is_excluded(&self, module: &Module) -> bool {
+        let version = module.parse_version().ok();
+
+        self.exclude.iter().any(|dependency| {
+            let excluded = &dependency.module;
+            excluded.module_path == module.module_path
+                && (excluded.version == module.version
+                    || version.is_some() && excluded.parse_version().ok() == version)
+        })
+    }
+
+    /// Returns `true` if `version` of this module is retracted by a `retract`
+    /// entry.
+    ///
+    /// Versions are compared as semantic versions, so a retraction of `v1.2`
+    /// retracts `v1.2.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::{GoMod, ModuleRetract, VersionInterval};
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example.com/lib
+    ///
+    /// retract (
+    ///     v1.0.0
+    ///     [v1.2.0, v1.2.5]
+    /// )
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    /// let version = "v1.2.3".parse().unwrap();
+    ///
+    /// assert!(go_mod.is_retracted(&version));
+    /// assert_eq!(
+    ///     go_mod.retracted_by(&version),
+    ///     vec![&ModuleRetract {
+    ///         interval: VersionInterval::Range("v1.2.0".to_string(), "v1.2.5".to_string()),
+    ///         rationale: None,
+    ///     }]
+    /// );
+    /// assert!(!go_mod.is_retracted(&"v1.1.0".parse().unwrap()));
+    /// ```
+    #[must_use]
+    pub fn is_retracted(&self, version: &Version) -> bool {
+        self.retract
+            .iter()
+            .any(|retract| retract.interval.contains(version))
+    }
+
+    /// Returns the `retract` entries retracting `version`, in file order.
+    #[must_use]
+    pub fn retracted_by(&self, version: &Version) -> Vec<&ModuleRetract> {
+        self.retract
+            .iter()
+            .filter(|retract| retract.interval.contains(version))
+            .collect()
+    }
+}
+
+impl VersionInterval {
+    /// Returns `true` if `version` lies within the retracted interval,
+    /// bounds included.
+    ///
+    /// Build metadata is ignored like in Go, so retracting `v2.0.0` also
+    /// retracts `v2.0.0+incompatible`. Entries with invalid versions retract
+    /// nothing.
+    #[must_use]
+    pub fn contains(&self, version: &Version) -> bool {
+        self.parse_bounds().map_or(false, |(low, high)| {
+            low.cmp_precedence(version) != Ordering::Greater
+                && version.cmp_precedence(&high) != Ordering::Greater
+        })
+    }
+}
@@ -0,0 +1,119 @@
+//! Quoting of values, following Go's `modfile.AutoQuote` and
+//! `strconv.Unquote`: values may be written as interpreted (`"..."`) or raw
+//! (`` `...` ``) string literals, as needed for paths containing spaces.
+
+use crate::syntax::PUNCTUATION;
+use std::fmt::Write;
+
+/// Returns `true`, if `value` must be quoted to be read back as a single
+/// token, like Go's `modfile.MustQuote`.
+pub fn must_quote(value: &str) -> bool {
+    value.is_empty()
+        || value.contains("//")
+        || value.contains("/*")
+        || value.chars().any(|c| match c {
+            ' ' | '"' | '\'' | '`' => true,
+            c if PUNCTUATION.contains(&c) => value.len() > 1,
+            c => !is_print(c),
+        })
+}
+
+/// Quotes `value`, if it must be quoted.
+pub fn auto_quote(value: &str) -> String {
+    if must_quote(value) {
+        quote(value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// The interpreted string literal of `value`, like Go's `strconv.Quote`.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if is_print(c) => quoted.push(c),
+            '\u{7}' => quoted.push_str("\\a"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{b}' => quoted.push_str("\\v"),
+            c if c < ' ' || c == '\u{7f}' => {
+                let _ = write!(quoted, "\\x{:02x}", c as u32);
+            }
+            c if c <= '\u{ffff}' => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => {
+                let _ = write!(quoted, "\\U{:08x}", c as u32);
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Approximates Go's `unicode.IsPrint`: graphic characters and the ASCII
+/// space.
+fn is_print(c: char) -> bool {
+    c == ' ' || !(c.is_control() || c.is_whitespace())
+}
+
+/// The value of a token, unquoting string literals.
+pub fn token_value(token: &str) -> String {
+    unquote(token).unwrap_or_else(|| token.to_string())
+}
+
+/// Unquotes an interpreted or raw string literal, like Go's
+/// `strconv.Unquote`. Returns `None`, if `literal` is none or invalid.
+pub fn unquote(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('`').and_then(|s| s.strip_suffix('`')) {
+        return (!raw.contains('`')).then(|| raw.to_string());
+    }
+
+    let mut chars = literal.strip_prefix('"')?.strip_suffix('"')?.chars();
+    // `\x` and octal escapes stand for bytes, which must form UTF-8
+    let mut bytes = Vec::with_capacity(literal.len());
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '"' | '\n' => return None,
+            '\\' => match chars.next()? {
+                'a' => '\u{7}',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'v' => '\u{b}',
+                c @ ('\\' | '"') => c,
+                'x' => {
+                    bytes.push(u8::from_str_radix(&take(&mut chars, 2)?, 16).ok()?);
+                    continue;
+                }
+                c @ '0'..='7' => {
+                    let digits = format!("{c}{}", take(&mut chars, 2)?);
+                    bytes.push(u8::from_str_radix(&digits, 8).ok()?);
+                    continue;
+                }
+                'u' => char::from_u32(u32::from_str_radix(&take(&mut chars, 4)?, 16).ok()?)?,
+                'U' => char::from_u32(u32::from_str_radix(&take(&mut chars, 8)?, 16).ok()?)?,
+                _ => return None,
+            },
+            c => c,
+        };
+        bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Takes the next `n` characters, which must be hexadecimal or octal digits.
+fn take(chars: &mut std::str::Chars<'_>, n: usize) -> Option<String> {
+    let digits: String = chars.take(n).collect();
+    (digits.len() == n && digits.chars().all(|c| c.is_ascii_hexdigit())).then_some(digits)
+}
@@ -0,0 +1,126 @@
+use crate::{GoMod, Module, Replacement, Version};
+use std::fmt;
+
+/// An error for a module replaced by several `replace` entries with
+/// different replacements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceError {
+    pub module_path: String,
+    /// The replaced version, `None` for conflicting replacements of all
+    /// versions.
+    pub version: Option<String>,
+    /// The conflicting replacements, in file order.
+    pub replacements: Vec<Replacement>,
+}
+
+impl fmt::Display for ReplaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conflicting replacements for {}", self.module_path)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{version}")?;
+        }
+        self.replacements
+            .iter()
+            .try_for_each(|replacement| write!(f, "\n\t{replacement}"))
+    }
+}
+
+impl std::error::Error for ReplaceError {}
+
+impl GoMod {
+    /// Returns the module or local directory used for the module
+    /// `module_path` at `version`, applying the `replace` entries like Go.
+    ///
+    /// A replacement of this exact version takes precedence over one of all
+    /// versions. Versions are compared as semantic versions, so `v0.19`
+    /// matches a replacement of `v0.19.0`. The module itself is returned if
+    /// neither exists. Repeated entries for the same module and version are
+    /// allowed only if they are identical.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gomod_parser::{GoMod, Module, Replacement};
+    /// use std::str::FromStr;
+    ///
+    /// let input = r#"
+    /// module example.com/app
+    ///
+    /// replace (
+    ///     golang.org/x/net => golang.org/x/net v0.21.0
+    ///     golang.org/x/net v0.19.0 => ../net
+    /// )
+    /// "#;
+    ///
+    /// let go_mod = GoMod::from_str(input).unwrap();
+    ///
+    /// assert_eq!(
+    ///     go_mod.effective_module("golang.org/x/net", "v0.19.0").unwrap(),
+    ///     Replacement::FilePath("../net".to_string())
+    /// );
+    /// assert_eq!(
+    ///     go_mod.effective_module("golang.org/x/net", "v0.20.0").unwrap(),
+    ///     Replacement::Module(Module {
+    ///         module_path: "golang.org/x/net".to_string(),
+    ///         version: "v0.21.0".to_string(),
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module is replaced differently by several
+    /// entries, for either this version or all versions.
+    pub fn effective_module(
+        &self,
+        module_path: &str,
+        version: &str,
+    ) -> Result<Replacement, ReplaceError> {
+        let exact = self.replacement(module_path, Some(version))?;
+        let wildcard = self.replacement(module_path, None)?;
+
+        Ok(exact.or(wildcard).cloned().unwrap_or_else(|| {
+            Replacement::Module(Module {
+                module_path: module_path.to_string(),
+                version: version.to_string(),
+            })
+        }))
+    }
+
+    /// Returns the replacement of the module `module_path` at `version`, or
+    /// of all its versions if `version` is `None`.
+    fn replacement(
+        &self,
+        module_path: &str,
+        version: Option<&str>,
+    ) -> Result<Option<&Replacement>, ReplaceError> {
+        let parsed = version.and_then(|version| version.parse::<Version>().ok());
+        let matches = |replaced: Option<&str>| match (replaced, version) {
+            (Some(replaced), Some(version)) => {
+                replaced == version
+                    || parsed.is_some() && replaced.parse::<Version>().ok() == parsed
+            }
+            (replaced, version) => replaced == version,
+        };
+
+        let mut replacements: Vec<&Replacement> = Vec::new();
+        for replacement in &self.replace {
+            if replacement.module_path == module_path
+                && matches(replacement.version.as_deref())
+                && !replacements.contains(&&replacement.replacement)
+            {
+                replacements.push(&replacement.replacement);
+            }
+        }
+
+        if replacements.len() > 1 {
+            return Err(ReplaceError {
+                module_path: module_path.to_string(),
+                version: version.map(ToString::to_string),
+                replacements: replacements.into_iter().cloned().collect(),
+            });
+        }
+
+        Ok(replacements.pop())
+    }
+}
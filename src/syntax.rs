@@ -0,0 +1,629 @@
+//! A lossless concrete syntax tree of a `go.mod` file.
+//!
+//! Unlike [`GoMod`](crate::GoMod), a [`FileSyntax`] keeps every byte of its
+//! input: whitespace, blank lines, comments, the grouping of directives into
+//! blocks and their order. Rendering it with [`Display`](std::fmt::Display)
+//! regenerates the original text exactly.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gomod_parser::syntax::{FileSyntax, Stmt};
+//! use std::str::FromStr;
+//!
+//! let input = "module github.com/example\n\nrequire (\n\tgolang.org/x/net v0.20.0 // indirect\n)\n";
+//!
+//! let syntax = FileSyntax::from_str(input).unwrap();
+//!
+//! let Stmt::Block(block) = &syntax.stmts[2] else {
+//!     panic!("expected a block")
+//! };
+//! assert_eq!(block.verb(), Some("require"));
+//! assert_eq!(block.lines[0].values().collect::<Vec<_>>(), vec!["golang.org/x/net", "v0.20.0"]);
+//! assert_eq!(syntax.to_string(), input);
+//! ```
+
+use crate::combinator::string_literal;
+use crate::quote::token_value;
+use crate::ParseError;
+use std::fmt;
+use winnow::combinator::{alt, cut_err, eof, fail, not, opt, peek, repeat, repeat_till};
+use winnow::error::{StrContext, StrContextValue};
+use winnow::stream::Stream;
+use winnow::token::{any, none_of, take_till};
+use winnow::{dispatch, ModalResult, Parser};
+
+/// Characters which form a token on their own.
+pub(crate) const PUNCTUATION: [char; 7] = ['(', ')', '[', ']', '{', '}', ','];
+
+/// The syntax tree of a whole file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSyntax {
+    pub stmts: Vec<Stmt>,
+}
+
+/// A top-level statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stmt {
+    /// A single line, which may be a directive, a comment or blank.
+    Line(Line),
+    /// A factored block like `require ( ... )`.
+    Block(LineBlock),
+}
+
+/// A factored block, e.g.
+///
+/// ```text
+/// require (
+///     golang.org/x/net v0.20.0
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineBlock {
+    /// The opening line, its tokens ending with `(`.
+    pub open: Line,
+    /// The lines inside the block, including blank and comment lines.
+    pub lines: Vec<Line>,
+    /// The closing line, its only token being `)`.
+    pub close: Line,
+}
+
+/// A single physical line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Line {
+    pub tokens: Vec<Token>,
+    /// A `//` comment ending the line.
+    pub comment: Option<Token>,
+    /// Whitespace after the last token or comment.
+    pub trailing: String,
+    /// The line ending: `\n`, `\r\n` or empty at the end of input.
+    pub newline: String,
+}
+
+/// A token, or a comment, together with the whitespace preceding it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Token {
+    pub space: String,
+    /// The raw text, including the quotes of string literals and the `//`
+    /// of comments.
+    pub text: String,
+}
+
+impl FileSyntax {
+    /// Iterates over all lines of the file, including the opening, inner and
+    /// closing lines of blocks.
+    pub fn lines(&self) -> impl Iterator<Item = &Line> {
+        self.stmts.iter().flat_map(|stmt| match stmt {
+            Stmt::Line(line) => vec![line],
+            Stmt::Block(block) => std::iter::once(&block.open)
+                .chain(&block.lines)
+                .chain(std::iter::once(&block.close))
+                .collect(),
+        })
+    }
+
+    /// The line ending used by the file, `\n` unless it already uses `\r\n`.
+    fn newline(&self) -> String {
+        self.lines()
+            .map(|line| line.newline.as_str())
+            .find(|newline| !newline.is_empty())
+            .unwrap_or("\n")
+            .to_string()
+    }
+
+    /// Replaces the arguments of all `verb` entries, for which `update`
+    /// returns new tokens given the unquoted ones. Returns `true`, if any
+    /// entry was updated.
+    pub(crate) fn update_entries(
+        &mut self,
+        verb: &str,
+        mut update: impl FnMut(&[String]) -> Option<Vec<String>>,
+    ) -> bool {
+        let mut updated = false;
+
+        for stmt in &mut self.stmts {
+            for (line, start) in stmt.entries_mut(verb) {
+                let args = line
+                    .values()
+                    .skip(start)
+                    .map(token_value)
+                    .collect::<Vec<_>>();
+                if let Some(args) = update(&args) {
+                    line.set_args(start, args);
+                    updated = true;
+                }
+            }
+        }
+
+        updated
+    }
+
+    /// Removes all `verb` entries whose unquoted arguments match `pred`,
+    /// together with the comment lines directly above them. Blocks left
+    /// without entries are removed as well. Returns `true`, if any entry was
+    /// removed.
+    pub(crate) fn remove_entries(
+        &mut self,
+        verb: &str,
+        mut pred: impl FnMut(&[String]) -> bool,
+    ) -> bool {
+        let mut matches = |line: &Line, start: usize| {
+            pred(
+                &line
+                    .values()
+                    .skip(start)
+                    .map(token_value)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let mut removed = false;
+        let mut i = 0;
+
+        while i < self.stmts.len() {
+            let remove_stmt = match &mut self.stmts[i] {
+                Stmt::Line(line) if line.verb() == Some(verb) => matches(line, 1),
+                Stmt::Block(block) if block.verb() == Some(verb) => {
+                    let mut j = 0;
+                    while j < block.lines.len() {
+                        if block.lines[j].tokens.is_empty() || !matches(&block.lines[j], 0) {
+                            j += 1;
+                            continue;
+                        }
+                        block.lines.remove(j);
+                        while j > 0 && block.lines[j - 1].is_comment() {
+                            block.lines.remove(j - 1);
+                            j -= 1;
+                        }
+                        removed = true;
+                    }
+                    block.lines.iter().all(|line| line.tokens.is_empty())
+                }
+                _ => false,
+            };
+
+            if remove_stmt {
+                let mut start = i;
+                while start > 0
+                    && matches!(&self.stmts[start - 1], Stmt::Line(line) if line.is_comment())
+                {
+                    start -= 1;
+                }
+                i = self.remove_stmts(start, i + 1);
+                removed = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        removed
+    }
+
+    /// Removes the statements from `index` to `end` and a blank line above
+    /// them, if they were surrounded by blank lines or the last statements.
+    /// Returns the index of the statement following the removed ones.
+    fn remove_stmts(&mut self, index: usize, end: usize) -> usize {
+        self.stmts.drain(index..end);
+
+        let is_blank =
+            |stmt: Option<&Stmt>| matches!(stmt, Some(Stmt::Line(line)) if line.is_blank());
+        if index > 0
+            && is_blank(self.stmts.get(index - 1))
+            && (index == self.stmts.len() || is_blank(self.stmts.get(index)))
+        {
+            self.stmts.remove(index - 1);
+            return index - 1;
+        }
+
+        index
+    }
+
+    /// Adds a `tokens[0]` entry after the last one of the same verb, like
+    /// `modfile`'s `addLine`: it is appended to a block, a single line is
+    /// converted into a block, and without any it is added at the end.
+    pub(crate) fn add_entry(&mut self, tokens: Vec<String>) {
+        let newline = self.newline();
+        let verb = tokens[0].as_str();
+        let last = self.stmts.iter().rposition(|stmt| match stmt {
+            Stmt::Line(line) => line.verb() == Some(verb),
+            Stmt::Block(block) => block.verb() == Some(verb),
+        });
+
+        let Some(i) = last else {
+            self.push_line(Line::new(tokens));
+            return;
+        };
+
+        match &mut self.stmts[i] {
+            Stmt::Block(block) => {
+                let indent = block
+                    .lines
+                    .iter()
+                    .find(|line| !line.is_blank())
+                    .map_or("\t", Line::indent)
+                    .to_string();
+                // keep blank lines before the closing `)` at the end
+                let at = block
+                    .lines
+                    .iter()
+                    .rposition(|line| !line.is_blank())
+                    .map_or(0, |j| j + 1);
+                block
+                    .lines
+                    .insert(at, Line::indented(&indent, &tokens[1..], &newline));
+            }
+            Stmt::Line(line) => {
+                let mut first = line.clone();
+                let keyword = first.tokens.remove(0);
+                first.newline.clone_from(&newline);
+
+                let mut open = Line {
+                    tokens: vec![keyword, Token::new(" ", "(")],
+                    comment: None,
+                    trailing: String::new(),
+                    newline: newline.clone(),
+                };
+                // a line without arguments, like a bare `require`, holds no
+                // entry, so only its comment is kept
+                let mut lines = Vec::with_capacity(2);
+                if first.tokens.is_empty() {
+                    open.comment = first.comment;
+                } else {
+                    first.tokens[0].space = "\t".to_string();
+                    lines.push(first);
+                }
+                lines.push(Line::indented("\t", &tokens[1..], &newline));
+
+                let mut close = Line::new([")"]);
+                close.newline = if line.newline.is_empty() {
+                    String::new()
+                } else {
+                    newline.clone()
+                };
+
+                self.stmts[i] = Stmt::Block(LineBlock { open, lines, close });
+            }
+        }
+    }
+
+    /// Inserts `line` after the statement at `index`, separated by blank lines.
+    pub(crate) fn insert_line_after(&mut self, index: usize, mut line: Line) {
+        let newline = self.newline();
+        line.newline.clone_from(&newline);
+        self.stmts[index].terminate(&newline);
+
+        let blank = || {
+            Stmt::Line(Line {
+                newline: newline.clone(),
+                ..Line::default()
+            })
+        };
+        let is_blank = matches!(self.stmts.get(index + 1), Some(Stmt::Line(l)) if l.is_blank());
+        let end = index + 1 == self.stmts.len();
+
+        let mut stmts = vec![blank(), Stmt::Line(line)];
+        if !end && !is_blank {
+            stmts.push(blank());
+        }
+        let at = index + 1;
+        self.stmts.splice(at..at, stmts);
+    }
+
+    /// Appends `line` at the end of the file, separated by a blank line.
+    fn push_line(&mut self, mut line: Line) {
+        let newline = self.newline();
+        line.newline.clone_from(&newline);
+
+        if let Some(last) = self.stmts.last_mut() {
+            last.terminate(&newline);
+            if !matches!(last, Stmt::Line(l) if l.is_blank()) {
+                self.stmts.push(Stmt::Line(Line {
+                    newline,
+                    ..Line::default()
+                }));
+            }
+        }
+        self.stmts.push(Stmt::Line(line));
+    }
+
+    /// The index of the last top-level statement of `verb`.
+    pub(crate) fn position(&self, verb: &str) -> Option<usize> {
+        self.stmts.iter().rposition(|stmt| match stmt {
+            Stmt::Line(line) => line.verb() == Some(verb),
+            Stmt::Block(block) => block.verb() == Some(verb),
+        })
+    }
+}
+
+impl Stmt {
+    /// The lines holding `verb` entries, with the index of their first argument.
+    fn entries_mut<'a>(&'a mut self, verb: &str) -> Vec<(&'a mut Line, usize)> {
+        match self {
+            Self::Line(line) if line.verb() == Some(verb) => vec![(line, 1)],
+            Self::Block(block) if block.verb() == Some(verb) => block
+                .lines
+                .iter_mut()
+                .filter(|line| !line.tokens.is_empty())
+                .map(|line| (line, 0))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Ends the last line of the statement with `newline`, if it has none.
+    fn terminate(&mut self, newline: &str) {
+        let line = match self {
+            Self::Line(line) => line,
+            Self::Block(block) => &mut block.close,
+        };
+        if line.newline.is_empty() {
+            line.newline = newline.to_string();
+        }
+    }
+}
+
+impl std::str::FromStr for FileSyntax {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let stmts = file
+            .parse(input)
+            .map_err(|e| ParseError::new(input, e.offset(), e.inner()))?;
+
+        Ok(Self { stmts })
+    }
+}
+
+impl LineBlock {
+    /// The first token of the opening line, e.g. `require`.
+    #[must_use]
+    pub fn verb(&self) -> Option<&str> {
+        self.open.tokens.first().map(|t| t.text.as_str())
+    }
+}
+
+impl Line {
+    /// Creates a line of single-space separated `tokens`, ended with `\n`.
+    pub fn new<I, S>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tokens: tokens
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| Token::new(if i == 0 { "" } else { " " }, text))
+                .collect(),
+            comment: None,
+            trailing: String::new(),
+            newline: "\n".to_string(),
+        }
+    }
+
+    fn indented(indent: &str, tokens: &[String], newline: &str) -> Self {
+        let mut line = Self::new(tokens.iter().cloned());
+        line.tokens[0].space = indent.to_string();
+        line.newline = newline.to_string();
+        line
+    }
+
+    /// Replaces the tokens from `start` on by `args`, keeping the whitespace
+    /// before the existing ones.
+    fn set_args(&mut self, start: usize, args: Vec<String>) {
+        self.tokens.truncate(start + args.len());
+        for (i, arg) in args.into_iter().enumerate() {
+            match self.tokens.get_mut(start + i) {
+                Some(token) => token.text = arg,
+                None => self.tokens.push(Token::new(" ", arg)),
+            }
+        }
+    }
+
+    /// Iterates over the raw text of the tokens.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().map(|t| t.text.as_str())
+    }
+
+    /// The first token, e.g. the directive keyword of a top-level line.
+    #[must_use]
+    pub fn verb(&self) -> Option<&str> {
+        self.tokens.first().map(|t| t.text.as_str())
+    }
+
+    /// The whitespace before the first token or comment.
+    #[must_use]
+    pub fn indent(&self) -> &str {
+        self.tokens
+            .first()
+            .or(self.comment.as_ref())
+            .map_or("", |t| t.space.as_str())
+    }
+
+    /// Returns `true` for lines without tokens and without comment.
+    #[must_use]
+    pub fn is_blank(&self) -> bool {
+        self.tokens.is_empty() && self.comment.is_none()
+    }
+
+    /// Returns `true` for lines holding nothing but a comment.
+    #[must_use]
+    pub fn is_comment(&self) -> bool {
+        self.tokens.is_empty() && self.comment.is_some()
+    }
+
+    fn opens_block(&self) -> bool {
+        self.tokens.len() > 1 && self.tokens.last().map(|t| t.text.as_str()) == Some("(")
+    }
+
+    fn closes_block(&self) -> bool {
+        self.tokens.first().map(|t| t.text.as_str()) == Some(")")
+    }
+}
+
+impl Token {
+    pub fn new(space: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            space: space.into(),
+            text: text.into(),
+        }
+    }
+}
+
+impl fmt::Display for FileSyntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.stmts.iter().try_for_each(|stmt| write!(f, "{stmt}"))
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line(line) => write!(f, "{line}"),
+            Self::Block(block) => write!(f, "{block}"),
+        }
+    }
+}
+
+impl fmt::Display for LineBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.open)?;
+        self.lines.iter().try_for_each(|line| write!(f, "{line}"))?;
+        write!(f, "{}", self.close)
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokens.iter().try_for_each(|t| write!(f, "{t}"))?;
+        if let Some(comment) = &self.comment {
+            write!(f, "{comment}")?;
+        }
+        write!(f, "{}{}", self.trailing, self.newline)
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.space, self.text)
+    }
+}
+
+fn file(input: &mut &str) -> ModalResult<Vec<Stmt>> {
+    let (stmts, _) = repeat_till(0.., stmt, eof).parse_next(input)?;
+
+    Ok(stmts)
+}
+
+fn stmt(input: &mut &str) -> ModalResult<Stmt> {
+    let start = input.checkpoint();
+    let open = line.parse_next(input)?;
+
+    if open.closes_block() {
+        input.reset(&start);
+        return cut_err(fail)
+            .context(StrContext::Label("block"))
+            .context(StrContext::Expected(StrContextValue::Description(
+                "opening `(`",
+            )))
+            .parse_next(input);
+    }
+    if !open.opens_block() {
+        return Ok(Stmt::Line(open));
+    }
+
+    let (lines, close) = cut_err(repeat_till(
+        0..,
+        // only fails at the end of input, as `)` lines are tried first
+        line.verify(|l: &Line| !l.closes_block())
+            .context(StrContext::Expected(')'.into())),
+        close_line,
+    ))
+    .context(StrContext::Label("block"))
+    .parse_next(input)?;
+
+    Ok(Stmt::Block(LineBlock { open, lines, close }))
+}
+
+fn close_line(input: &mut &str) -> ModalResult<Line> {
+    let start = input.checkpoint();
+    let close = line.verify(Line::closes_block).parse_next(input)?;
+
+    if close.tokens.len() > 1 {
+        input.reset(&start);
+        return cut_err(fail)
+            .context(StrContext::Expected(StrContextValue::Description(
+                "newline after `)`",
+            )))
+            .parse_next(input);
+    }
+
+    Ok(close)
+}
+
+fn line(input: &mut &str) -> ModalResult<Line> {
+    // every line consumes input, so stop at its end
+    not(eof).parse_next(input)?;
+
+    let tokens = repeat(0.., token).parse_next(input)?;
+    let comment = opt(comment).parse_next(input)?;
+    let trailing = space.parse_next(input)?;
+    let newline = alt(("\r\n", "\n", eof))
+        .context(StrContext::Expected('\n'.into()))
+        .parse_next(input)?;
+
+    // trailing whitespace of a comment is kept apart, like after tokens
+    let (comment, trailing) = match comment {
+        Some((space, text)) => {
+            let trimmed = text.trim_end_matches([' ', '\t']);
+            (
+                Some(Token::new(space, trimmed)),
+                format!("{}{trailing}", &text[trimmed.len()..]),
+            )
+        }
+        None => (None, trailing.to_string()),
+    };
+
+    Ok(Line {
+        tokens,
+        comment,
+        trailing,
+        newline: newline.to_string(),
+    })
+}
+
+fn token(input: &mut &str) -> ModalResult<Token> {
+    let (space, text) = (
+        space,
+        dispatch! {peek(any);
+            c if PUNCTUATION.contains(&c) => any.take(),
+            '"' | '`' => string_literal,
+            _ => identifier,
+        },
+    )
+        .parse_next(input)?;
+
+    Ok(Token::new(space, text))
+}
+
+fn comment<'a>(input: &mut &'a str) -> ModalResult<(&'a str, &'a str)> {
+    (space, ("//", take_till(0.., ['\r', '\n'])).take()).parse_next(input)
+}
+
+/// Whitespace within a line, i.e. spaces, tabs and `\r` not ending the line.
+fn space<'a>(input: &mut &'a str) -> ModalResult<&'a str> {
+    repeat::<_, _, (), _, _>(0.., alt((' ', '\t', ('\r', not('\n')).map(|_| '\r'))))
+        .take()
+        .parse_next(input)
+}
+
+fn identifier<'a>(input: &mut &'a str) -> ModalResult<&'a str> {
+    repeat::<_, _, (), _, _>(
+        1..,
+        (
+            not("//"),
+            none_of(|c: char| c.is_whitespace() || PUNCTUATION.contains(&c)),
+        ),
+    )
+    .take()
+    .parse_next(input)
+}
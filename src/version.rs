@@ -0,0 +1,212 @@
+use crate::parser::expected;
+use crate::ParseError;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use winnow::ascii::digit0;
+use winnow::combinator::{alt, cut_err, eof, opt, preceded, separated};
+use winnow::error::StrContext;
+use winnow::token::{one_of, take_while};
+use winnow::{ModalResult, Parser};
+
+/// A module version following Go's semantic versioning rules.
+///
+/// Versions are written `vMAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]`, where the
+/// shorthands `vMAJOR` and `vMAJOR.MINOR` stand for `vMAJOR.0.0` and
+/// `vMAJOR.MINOR.0`. A parsed version is kept and rendered in this canonical
+/// form, so `v1.2` equals `v1.2.0`.
+///
+/// Versions are ordered like Go's `semver.Compare`: by their numbers, and a
+/// prerelease before its release. Build metadata, like `+incompatible`, does
+/// not affect their precedence and only breaks ties to keep the order
+/// consistent with equality.
+///
+/// # Example
+///
+/// ```rust
+/// use gomod_parser::Version;
+/// use std::str::FromStr;
+///
+/// let v1_2: Version = "v1.2".parse().unwrap();
+/// let rc: Version = "v1.2.0-rc.1".parse().unwrap();
+///
+/// assert_eq!(v1_2.to_string(), "v1.2.0");
+/// assert!(rc < v1_2);
+/// assert!("v2.0.0+incompatible".parse::<Version>().unwrap().is_incompatible());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The dot-separated prerelease identifiers, without the leading `-`.
+    pub prerelease: Option<String>,
+    /// The dot-separated build metadata, without the leading `+`.
+    pub build: Option<String>,
+}
+
+impl Version {
+    /// Returns `true` for versions with `+incompatible` build metadata, which
+    /// mark major versions of modules without `go.mod` file.
+    #[must_use]
+    pub fn is_incompatible(&self) -> bool {
+        self.build.as_deref() == Some("incompatible")
+    }
+
+    /// Returns `true` for prerelease versions, like `v1.2.0-rc.1`.
+    #[must_use]
+    pub const fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    /// Compares the precedence of versions, ignoring their build metadata
+    /// like Go's `semver.Compare`, so `v2.0.0+incompatible` equals `v2.0.0`.
+    #[must_use]
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        version
+            .context(StrContext::Label("version"))
+            .parse(input)
+            .map_err(|e| ParseError::new(input, e.offset(), e.inner()))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(prerelease) = &self.prerelease {
+            write!(f, "-{prerelease}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares prerelease identifiers: numeric ones numerically and before
+/// alphanumeric ones, which are compared in ASCII order.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let is_numeric = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (is_numeric(a), is_numeric(b)) {
+                (true, true) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (false, false) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn version(input: &mut &str) -> ModalResult<Version> {
+    let _ = 'v'.context(expected("`v`")).parse_next(input)?;
+
+    let mut res = Version {
+        major: number
+            .context(expected("major version"))
+            .parse_next(input)?,
+        minor: 0,
+        patch: 0,
+        prerelease: None,
+        build: None,
+    };
+
+    // the shorthands may not have a prerelease or build metadata
+    if let Some(minor) = opt(preceded(
+        '.',
+        cut_err(number.context(expected("minor version"))),
+    ))
+    .parse_next(input)?
+    {
+        res.minor = minor;
+        if let Some(patch) = opt(preceded(
+            '.',
+            cut_err(number.context(expected("patch version"))),
+        ))
+        .parse_next(input)?
+        {
+            res.patch = patch;
+            res.prerelease = opt(preceded(
+                '-',
+                cut_err(identifiers(true).context(expected("prerelease"))),
+            ))
+            .parse_next(input)?;
+            res.build = opt(preceded(
+                '+',
+                cut_err(identifiers(false).context(expected("build metadata"))),
+            ))
+            .parse_next(input)?;
+        }
+    }
+
+    let _ = cut_err(eof.context(expected("end of version"))).parse_next(input)?;
+
+    Ok(res)
+}
+
+/// A decimal number without leading zeros.
+pub fn number(input: &mut &str) -> ModalResult<u64> {
+    alt(("0", (one_of('1'..='9'), digit0).take()))
+        .try_map(str::parse)
+        .parse_next(input)
+}
+
+/// Dot-separated identifiers of ASCII alphanumerics and hyphens, numeric ones
+/// without leading zeros if `prerelease` is set.
+fn identifiers(prerelease: bool) -> impl FnMut(&mut &str) -> ModalResult<String> {
+    move |input: &mut &str| {
+        separated::<_, _, (), _, _, _, _>(
+            1..,
+            take_while(1.., |c: char| c.is_ascii_alphanumeric() || c == '-').verify(
+                |identifier: &str| {
+                    !prerelease
+                        || identifier == "0"
+                        || !identifier.starts_with('0')
+                        || !identifier.bytes().all(|b| b.is_ascii_digit())
+                },
+            ),
+            '.',
+        )
+        .take()
+        .map(ToString::to_string)
+        .parse_next(input)
+    }
+}
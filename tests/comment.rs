@@ -0,0 +1,262 @@
+use gomod_parser::{
+    Comments, GoMod, Module, ModuleDependency, ModuleRetract, PathEntry, VersionInterval,
+};
+use std::str::FromStr;
+
+#[test]
+fn test_module_deprecation() {
+    let input = r#"// Package service.
+//
+// Deprecated: use example.com/service/v2
+// instead.
+module example.com/service
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(
+        go_mod.deprecated,
+        Some("use example.com/service/v2\ninstead.".to_string())
+    );
+    assert_eq!(go_mod.comment, vec!["Package service.".to_string()]);
+
+    for (input, deprecated) in [
+        (
+            "module example.com/service // Deprecated: use v2\n",
+            Some("use v2"),
+        ),
+        ("// Deprecated:\nmodule example.com/service\n", None),
+        (
+            "// Not Deprecated: use v2\nmodule example.com/service\n",
+            None,
+        ),
+        (
+            "// Deprecated: use v2\n\nmodule example.com/service\n",
+            None,
+        ),
+    ] {
+        let go_mod = GoMod::from_str(input).unwrap();
+
+        assert_eq!(go_mod.deprecated.as_deref(), deprecated, "{input}");
+    }
+}
+
+#[test]
+fn test_retract_rationale() {
+    let input = r#"module example.com/lib
+
+// published accidentally
+retract v1.0.0
+
+retract v1.0.1 // broken build
+
+// security issues
+retract (
+	[v1.1.0, v1.1.5]
+
+	// wrong module path
+	// in go.mod
+	v1.2.0
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    let rationales: Vec<_> = go_mod
+        .retract
+        .iter()
+        .map(|retract| retract.rationale.as_deref())
+        .collect();
+    assert_eq!(
+        rationales,
+        vec![
+            Some("published accidentally"),
+            Some("broken build"),
+            Some("security issues"),
+            Some("wrong module path\nin go.mod"),
+        ]
+    );
+    assert!(go_mod.comment.is_empty());
+}
+
+#[test]
+fn test_entry_comments() {
+    let input = r#"// Service.
+module example.com/service
+
+go 1.22 // minimum for range over int
+
+// direct dependencies
+require (
+	// pinned due to CVE-2024-1234
+	golang.org/x/net v0.20.0 // until v0.21.0 is vetted
+
+	golang.org/x/text v0.14.0 // indirect
+)
+
+require golang.org/x/sys v0.16.0 // for unix.Pledge
+
+replace golang.org/x/net => ../net // local fork
+
+tool golang.org/x/tools/cmd/stringer // enum strings
+
+ignore (
+	// generated
+	./testdata
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    let comments = |before: &[&str], suffix: Option<&str>| Comments {
+        before: before.iter().map(ToString::to_string).collect(),
+        suffix: suffix.map(ToString::to_string),
+    };
+    assert_eq!(go_mod.go, Some("1.22".to_string()));
+    assert_eq!(
+        go_mod.comment,
+        vec![
+            "Service.".to_string(),
+            "minimum for range over int".to_string(),
+            "direct dependencies".to_string(),
+        ]
+    );
+    assert_eq!(
+        go_mod
+            .require
+            .iter()
+            .map(|dependency| &dependency.comments)
+            .collect::<Vec<_>>(),
+        vec![
+            &comments(
+                &["pinned due to CVE-2024-1234"],
+                Some("until v0.21.0 is vetted")
+            ),
+            &comments(&[], None),
+            &comments(&[], Some("for unix.Pledge")),
+        ]
+    );
+    assert!(go_mod.require[1].indirect);
+    assert_eq!(
+        go_mod.replace[0].comments,
+        comments(&[], Some("local fork"))
+    );
+    assert_eq!(go_mod.tool[0].comments, comments(&[], Some("enum strings")));
+    assert_eq!(go_mod.ignore[0].comments, comments(&["generated"], None));
+}
+
+#[test]
+fn test_indirect_markers() {
+    let input = r#"module example.com/service
+
+require (
+	example.com/a v1.0.0 // indirect
+	example.com/b v1.0.0 //indirect
+	example.com/c v1.0.0 //	indirect
+	example.com/d v1.0.0 // indirect; pinned for tests
+	example.com/e v1.0.0 //indirect;  see #42
+	example.com/f v1.0.0 // indirect pinned
+	example.com/g v1.0.0 // indirect;pinned
+	example.com/h v1.0.0 // indirect;
+	example.com/i v1.0.0 // indirectly
+	example.com/j v1.0.0 // Indirect
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    let markers: Vec<_> = go_mod
+        .require
+        .iter()
+        .map(|dependency| (dependency.indirect, dependency.comments.suffix.as_deref()))
+        .collect();
+    assert_eq!(
+        markers,
+        vec![
+            (true, None),
+            (true, None),
+            (true, None),
+            (true, Some("pinned for tests")),
+            (true, Some("see #42")),
+            (false, Some("indirect pinned")),
+            (false, Some("indirect;pinned")),
+            (false, Some("indirect;")),
+            (false, Some("indirectly")),
+            (false, Some("Indirect")),
+        ]
+    );
+}
+
+#[test]
+fn test_entry_comments_after_edit() {
+    let input = r#"module example.com/service
+
+// pinned
+require golang.org/x/net v0.20.0
+"#;
+
+    let mut go_mod = GoMod::from_str(input).unwrap();
+    go_mod.add_require("golang.org/x/text", "v0.14.0");
+
+    // like `modfile`, the comments move to the new block
+    assert_eq!(go_mod.comment, vec!["pinned".to_string()]);
+    assert_eq!(go_mod.require[0].comments, Comments::default());
+    assert_eq!(GoMod::from_str(&go_mod.syntax.to_string()).unwrap(), go_mod);
+}
+
+#[test]
+fn test_comments_roundtrip() {
+    let go_mod = GoMod {
+        module: "example.com/lib".to_string(),
+        deprecated: Some("use example.com/lib/v2".to_string()),
+        retract: vec![ModuleRetract {
+            interval: VersionInterval::Range("v1.0.0".to_string(), "v1.0.5".to_string()),
+            rationale: Some("broken\nbuild".to_string()),
+        }],
+        require: vec![
+            ModuleDependency {
+                module: Module {
+                    module_path: "golang.org/x/net".to_string(),
+                    version: "v0.20.0".to_string(),
+                },
+                indirect: true,
+                comments: Comments {
+                    before: vec!["pinned due to".to_string(), "CVE-2024-1234".to_string()],
+                    suffix: Some("until vetted".to_string()),
+                },
+            },
+            ModuleDependency {
+                module: Module {
+                    module_path: "golang.org/x/text".to_string(),
+                    version: "v0.14.0".to_string(),
+                },
+                indirect: false,
+                comments: Comments::default(),
+            },
+        ],
+        tool: vec![PathEntry {
+            path: "golang.org/x/tools/cmd/stringer".to_string(),
+            comments: Comments {
+                before: Vec::new(),
+                suffix: Some("enum strings".to_string()),
+            },
+        }],
+        comment: vec!["Library.".to_string()],
+        ..GoMod::default()
+    };
+
+    let rendered = go_mod.to_string();
+
+    assert!(rendered.contains(
+        "\t// pinned due to\n\t// CVE-2024-1234\n\tgolang.org/x/net v0.20.0 // indirect; until vetted\n"
+    ));
+    assert!(rendered.contains("tool golang.org/x/tools/cmd/stringer // enum strings\n"));
+
+    let reparsed = GoMod::from_str(&rendered).unwrap();
+
+    assert_eq!(reparsed.deprecated, go_mod.deprecated);
+    assert_eq!(reparsed.retract, go_mod.retract);
+    assert_eq!(reparsed.require, go_mod.require);
+    assert_eq!(reparsed.tool, go_mod.tool);
+    assert_eq!(reparsed.comment, go_mod.comment);
+}
@@ -0,0 +1,366 @@
+use gomod_parser::{GoMod, Module, Replacement};
+use indoc::indoc;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_set_require_version() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    require (
+        golang.org/x/net    v0.20.0 // indirect
+        golang.org/x/text   v0.14.0
+    )
+    "})
+    .unwrap();
+
+    assert!(gomod.set_require_version("golang.org/x/net", "v0.21.0"));
+    assert!(!gomod.set_require_version("golang.org/x/sys", "v0.16.0"));
+
+    assert_eq!(gomod.require[0].module.version, "v0.21.0".to_string());
+    assert!(gomod.require[0].indirect);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        require (
+            golang.org/x/net    v0.21.0 // indirect
+            golang.org/x/text   v0.14.0
+        )
+        "}
+    );
+}
+
+#[test]
+fn test_add_require() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    require (
+    	golang.org/x/net v0.20.0
+
+    )
+
+    // tools
+    require golang.org/x/tools v0.17.0
+    "})
+    .unwrap();
+
+    gomod.add_require("golang.org/x/net", "v0.21.0");
+    gomod.add_require("golang.org/x/text", "v0.14.0");
+
+    assert_eq!(gomod.require.len(), 3);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        require (
+        	golang.org/x/net v0.21.0
+
+        )
+
+        // tools
+        require (
+        	golang.org/x/tools v0.17.0
+        	golang.org/x/text v0.14.0
+        )
+        "}
+    );
+
+    assert_eq!(GoMod::from_str(&gomod.syntax.to_string()).unwrap(), gomod);
+}
+
+#[test]
+fn test_add_require_without_requirements() {
+    let mut gomod = GoMod::from_str("module github.com/example").unwrap();
+
+    gomod.add_require("golang.org/x/net", "v0.20.0");
+
+    assert_eq!(
+        gomod.syntax.to_string(),
+        "module github.com/example\n\nrequire golang.org/x/net v0.20.0\n"
+    );
+}
+
+#[test]
+fn test_add_require_after_bare_require() {
+    let (mut gomod, diagnostics) = GoMod::parse_with_diagnostics("module x.com/a\n\nrequire\n");
+    assert_eq!(diagnostics.len(), 1);
+
+    gomod.add_require("golang.org/x/net", "v0.20.0");
+
+    assert_eq!(
+        gomod.syntax.to_string(),
+        "module x.com/a\n\nrequire (\n\tgolang.org/x/net v0.20.0\n)\n"
+    );
+    assert_eq!(
+        GoMod::from_str(&gomod.syntax.to_string()).unwrap().require,
+        gomod.require
+    );
+}
+
+#[test]
+fn test_drop_require() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    require (
+        golang.org/x/net v0.20.0
+        // pinned
+        golang.org/x/text v0.14.0
+    )
+
+    // tools
+    require golang.org/x/tools v0.17.0
+
+    go 1.21
+    "})
+    .unwrap();
+
+    assert!(gomod.drop_require("golang.org/x/text"));
+    assert!(gomod.drop_require("golang.org/x/tools"));
+    assert!(!gomod.drop_require("golang.org/x/sys"));
+
+    assert_eq!(gomod.require.len(), 1);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        require (
+            golang.org/x/net v0.20.0
+        )
+
+        go 1.21
+        "}
+    );
+
+    assert!(gomod.drop_require("golang.org/x/net"));
+
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        go 1.21
+        "}
+    );
+}
+
+#[test]
+fn test_add_and_drop_exclude() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    exclude golang.org/x/net v0.19.1
+    "})
+    .unwrap();
+
+    gomod.add_exclude("golang.org/x/net", "v0.19.1");
+    gomod.add_exclude("golang.org/x/net", "v0.19.2");
+
+    assert_eq!(gomod.exclude.len(), 2);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {"
+        module github.com/example
+
+        exclude (
+        \tgolang.org/x/net v0.19.1
+        \tgolang.org/x/net v0.19.2
+        )
+        "}
+    );
+
+    assert!(gomod.drop_exclude("golang.org/x/net", "v0.19.1"));
+    assert!(!gomod.drop_exclude("golang.org/x/net", "v0.19.1"));
+
+    assert_eq!(gomod.exclude.len(), 1);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {"
+        module github.com/example
+
+        exclude (
+        \tgolang.org/x/net v0.19.2
+        )
+        "}
+    );
+}
+
+#[test]
+fn test_add_replace() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    replace (
+        golang.org/x/net v0.19.0 => example.com/fork/net v0.19.1 // fork
+        golang.org/x/net v0.20.0 => ../net
+        golang.org/x/text => ../text
+    )
+    "})
+    .unwrap();
+
+    gomod.add_replace(
+        "golang.org/x/text",
+        None,
+        Replacement::FilePath("../text2".to_string()),
+    );
+    gomod.add_replace(
+        "golang.org/x/sys",
+        Some("v0.16.0"),
+        Replacement::Module(Module {
+            module_path: "example.com/fork/sys".to_string(),
+            version: "v0.16.1".to_string(),
+        }),
+    );
+    gomod.add_replace(
+        "golang.org/x/net",
+        None,
+        Replacement::FilePath("../net".to_string()),
+    );
+
+    assert_eq!(gomod.replace.len(), 3);
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        replace (
+            golang.org/x/net => ../net // fork
+            golang.org/x/text => ../text2
+            golang.org/x/sys v0.16.0 => example.com/fork/sys v0.16.1
+        )
+        "}
+    );
+
+    assert_eq!(GoMod::from_str(&gomod.syntax.to_string()).unwrap(), gomod);
+}
+
+#[test]
+fn test_add_replace_quotes_paths() {
+    let mut gomod = GoMod::from_str(indoc! {r#"
+    module github.com/example
+
+    replace golang.org/x/net => "../my net"
+    "#})
+    .unwrap();
+
+    gomod.add_replace(
+        "golang.org/x/net",
+        None,
+        Replacement::FilePath("../our net".to_string()),
+    );
+    gomod.add_replace(
+        "golang.org/x/text",
+        None,
+        Replacement::FilePath("../text".to_string()),
+    );
+
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r#"
+        module github.com/example
+
+        replace (
+        	golang.org/x/net => "../our net"
+        	golang.org/x/text => ../text
+        )
+        "#}
+    );
+    assert_eq!(GoMod::from_str(&gomod.syntax.to_string()).unwrap(), gomod);
+
+    assert!(gomod.drop_replace("golang.org/x/net", None));
+    assert_eq!(gomod.replace.len(), 1);
+}
+
+#[test]
+fn test_drop_replace() {
+    let file_content = get_test_file_content("docker_docs.mod");
+    let mut gomod = GoMod::from_str(&file_content).unwrap();
+
+    assert!(!gomod.drop_replace("github.com/docker/cli", Some("v25.0.0")));
+    assert!(gomod.drop_replace("github.com/docker/cli", None));
+
+    assert!(gomod.replace.is_empty());
+    assert_eq!(
+        gomod.syntax.to_string(),
+        file_content[..file_content.find("\n\n// buildkit").unwrap() + 1]
+    );
+}
+
+#[test]
+fn test_set_go_and_toolchain() {
+    let mut gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    go 1.20
+
+    require golang.org/x/net v0.20.0
+    "})
+    .unwrap();
+
+    gomod.set_go("1.21");
+    gomod.set_toolchain("go1.21.1");
+
+    assert_eq!(gomod.go, Some("1.21".to_string()));
+    assert_eq!(gomod.toolchain, Some("go1.21.1".to_string()));
+    assert_eq!(
+        gomod.syntax.to_string(),
+        indoc! {r"
+        module github.com/example
+
+        go 1.21
+
+        toolchain go1.21.1
+
+        require golang.org/x/net v0.20.0
+        "}
+    );
+
+    gomod.set_toolchain("go1.22.0");
+
+    assert!(gomod.syntax.to_string().contains("\ntoolchain go1.22.0\n"));
+}
+
+#[test]
+fn test_set_go_without_go() {
+    let mut gomod =
+        GoMod::from_str("module github.com/example\nrequire golang.org/x/net v0.20.0\n").unwrap();
+
+    gomod.set_go("1.21");
+
+    assert_eq!(
+        gomod.syntax.to_string(),
+        "module github.com/example\n\ngo 1.21\n\nrequire golang.org/x/net v0.20.0\n"
+    );
+}
+
+#[test]
+fn test_edit_keeps_carriage_return() {
+    let file_content = get_test_file_content("docker_docs.mod")
+        .replace("\r", "") // Replace any current CR
+        .replace("\n", "\r\n"); // Replace LF with CRLF
+    let mut gomod = GoMod::from_str(&file_content).unwrap();
+
+    gomod.add_require("golang.org/x/net", "v0.20.0");
+    gomod.add_exclude("golang.org/x/net", "v0.19.1");
+
+    let output = gomod.syntax.to_string();
+    assert_eq!(output.matches('\n').count(), output.matches("\r\n").count());
+    assert!(output.contains("\tgolang.org/x/net v0.20.0\r\n)\r\n"));
+}
@@ -0,0 +1,497 @@
+use gomod_parser::format::format;
+use gomod_parser::syntax::FileSyntax;
+use gomod_parser::{
+    Comments, GoMod, Module, ModuleDependency, ModuleReplacement, ModuleRetract, Replacement,
+    UnknownDirective, VersionInterval,
+};
+use indoc::indoc;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+fn format_str(input: &str) -> String {
+    format(&FileSyntax::from_str(input).unwrap())
+}
+
+#[test]
+fn test_format_canonical_files_unchanged() {
+    for file_name in [
+        "compress.mod",
+        "docker_docs.mod",
+        "kubernetes.mod",
+        "prometheus.mod",
+    ] {
+        let content = get_test_file_content(file_name);
+
+        assert_eq!(format_str(&content), content, "{file_name}");
+    }
+}
+
+#[test]
+fn test_format_indents_blocks_with_tabs() {
+    let input = get_test_file_content("tool.mod");
+
+    let formatted = GoMod::from_str(&input).unwrap().format();
+
+    assert!(
+        formatted.contains("tool (\n\tgithub.com/golangci/golangci-lint/v2/cmd/golangci-lint\n")
+    );
+    assert_eq!(
+        GoMod::from_str(&formatted).unwrap(),
+        GoMod::from_str(&input).unwrap()
+    );
+    assert_eq!(format_str(&formatted), formatted);
+}
+
+#[test]
+fn test_format_spacing() {
+    let input = concat!(
+        "  module   github.com/example   \r\n",
+        "go 1.21\n",
+        "\n\n\n",
+        "  // retracted by accident\n",
+        "retract [ v1.0.0 ,v1.0.5 ]   //  comment\n",
+    );
+
+    assert_eq!(
+        format_str(input),
+        concat!(
+            "module github.com/example\n",
+            "\n",
+            "go 1.21\n",
+            "\n",
+            "// retracted by accident\n",
+            "retract [v1.0.0, v1.0.5] //  comment\n",
+        )
+    );
+}
+
+#[test]
+fn test_format_blocks() {
+    let input = concat!(
+        "module github.com/example\n",
+        "require (\n",
+        "\n",
+        "  golang.org/x/net v0.20.0\n",
+        "\n",
+        "\n",
+        "    // text\n",
+        "golang.org/x/text v0.14.0\n",
+        "\n",
+        ")\n",
+    );
+
+    assert_eq!(
+        format_str(input),
+        concat!(
+            "module github.com/example\n",
+            "\n",
+            "require (\n",
+            "\tgolang.org/x/net v0.20.0\n",
+            "\n",
+            "\t// text\n",
+            "\tgolang.org/x/text v0.14.0\n",
+            ")\n",
+        )
+    );
+}
+
+#[test]
+fn test_format_collapses_single_entry_blocks() {
+    let input = concat!(
+        "module github.com/example\n",
+        "\n",
+        "// net\n",
+        "require (\n",
+        "    // pinned\n",
+        "    golang.org/x/net v0.20.0\n",
+        ")\n",
+        "\n",
+        "exclude ( // excluded\n",
+        "    golang.org/x/net v0.19.0\n",
+        ")\n",
+        "\n",
+        "retract ( // broken\n",
+        "    v1.0.0 // tag was moved\n",
+        ")\n",
+        "\n",
+        "// empty blocks are dropped\n",
+        "replace (\n",
+        ")\n",
+        "\n",
+        "tool (\n",
+        "    golang.org/x/tools/cmd/stringer\n",
+        "    // trailing comment keeps the block\n",
+        ")\n",
+    );
+
+    assert_eq!(
+        format_str(input),
+        concat!(
+            "module github.com/example\n",
+            "\n",
+            "// net\n",
+            "// pinned\n",
+            "require golang.org/x/net v0.20.0\n",
+            "\n",
+            "exclude golang.org/x/net v0.19.0 // excluded\n",
+            "\n",
+            "// broken\n",
+            "retract v1.0.0 // tag was moved\n",
+            "\n",
+            "tool (\n",
+            "\tgolang.org/x/tools/cmd/stringer\n",
+            "\t// trailing comment keeps the block\n",
+            ")\n",
+        )
+    );
+}
+
+#[test]
+fn test_format_sorts_blocks() {
+    let input = concat!(
+        "module github.com/example\n",
+        "\n",
+        "go 1.21\n",
+        "\n",
+        "require (\n",
+        "\tb v1.0.0\n",
+        "\t// a is pinned\n",
+        "\ta v1.0.0\n",
+        ")\n",
+        "\n",
+        "exclude (\n",
+        "\ta v1.10.0\n",
+        "\ta v1.9.0\n",
+        "\t// duplicate\n",
+        "\ta v1.10.0\n",
+        ")\n",
+        "\n",
+        "replace a v1.0.0 => ../a\n",
+        "\n",
+        "replace (\n",
+        "\tc => ../c\n",
+        "\ta v1.0.0 => ../fork\n",
+        ")\n",
+        "\n",
+        "retract (\n",
+        "\tv1.0.0\n",
+        "\t[v1.2.0, v1.3.0]\n",
+        "\tv1.1.0\n",
+        ")\n",
+    );
+
+    assert_eq!(
+        format_str(input),
+        concat!(
+            "module github.com/example\n",
+            "\n",
+            "go 1.21\n",
+            "\n",
+            "require (\n",
+            "\t// a is pinned\n",
+            "\ta v1.0.0\n",
+            "\tb v1.0.0\n",
+            ")\n",
+            "\n",
+            "exclude (\n",
+            "\ta v1.9.0\n",
+            "\ta v1.10.0\n",
+            ")\n",
+            "\n",
+            "replace (\n",
+            "\ta v1.0.0 => ../fork\n",
+            "\tc => ../c\n",
+            ")\n",
+            "\n",
+            "retract (\n",
+            "\t[v1.2.0, v1.3.0]\n",
+            "\tv1.1.0\n",
+            "\tv1.0.0\n",
+            ")\n",
+        )
+    );
+
+    // before go 1.21, exclude blocks are sorted lexicographically
+    assert_eq!(
+        format_str("exclude (\n\ta v1.9.0\n\ta v1.10.0\n)\n"),
+        "exclude (\n\ta v1.10.0\n\ta v1.9.0\n)\n"
+    );
+}
+
+#[test]
+fn test_format_normalizes_indirect() {
+    let input = concat!(
+        "module github.com/example\n",
+        "\n",
+        "require golang.org/x/sys v0.16.0 //indirect\n",
+        "\n",
+        "require (\n",
+        "\tgolang.org/x/net v0.20.0 //   indirect\n",
+        "\tgolang.org/x/text v0.14.0 //indirect;   for tests\n",
+        "\tgolang.org/x/tools v0.17.0 //indirectly\n",
+        ")\n",
+        "\n",
+        "tool golang.org/x/tools/cmd/stringer //indirect\n",
+    );
+
+    assert_eq!(
+        format_str(input),
+        concat!(
+            "module github.com/example\n",
+            "\n",
+            "require golang.org/x/sys v0.16.0 // indirect\n",
+            "\n",
+            "require (\n",
+            "\tgolang.org/x/net v0.20.0 // indirect\n",
+            "\tgolang.org/x/text v0.14.0 // indirect; for tests\n",
+            "\tgolang.org/x/tools v0.17.0 //indirectly\n",
+            ")\n",
+            "\n",
+            "tool golang.org/x/tools/cmd/stringer //indirect\n",
+        )
+    );
+}
+
+#[test]
+fn test_display_reparses_to_equal_gomod() {
+    for file_name in [
+        "compress.mod",
+        "docker_docs.mod",
+        "godebug.mod",
+        "ignore.mod",
+        "iris.mod",
+        "kubernetes.mod",
+        "prometheus.mod",
+        "tool.mod",
+    ] {
+        let gomod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+
+        let rendered = gomod.to_string();
+
+        assert_eq!(GoMod::from_str(&rendered).unwrap(), gomod, "{file_name}");
+        // entries are rendered in model order, which formatting may sort
+        let formatted = format_str(&rendered);
+        let sorted = GoMod::from_str(&formatted).unwrap();
+        assert_eq!(sorted.to_string(), formatted, "{file_name}");
+    }
+}
+
+#[test]
+fn test_display_programmatic_gomod() {
+    let gomod = GoMod {
+        comment: vec!["Generated service".to_string()],
+        module: "github.com/example/service".to_string(),
+        deprecated: Some("use github.com/example/service/v2".to_string()),
+        go: Some("1.22".to_string()),
+        toolchain: Some("go1.22.1".to_string()),
+        godebug: HashMap::from([
+            ("panicnil".to_string(), "1".to_string()),
+            ("default".to_string(), "go1.21".to_string()),
+        ]),
+        require: vec![
+            ModuleDependency {
+                module: Module {
+                    module_path: "golang.org/x/net".to_string(),
+                    version: "v0.20.0".to_string(),
+                },
+                indirect: false,
+                comments: Comments::default(),
+            },
+            ModuleDependency {
+                module: Module {
+                    module_path: "golang.org/x/text".to_string(),
+                    version: "v0.14.0".to_string(),
+                },
+                indirect: true,
+                comments: Comments::default(),
+            },
+        ],
+        replace: vec![
+            ModuleReplacement {
+                module_path: "golang.org/x/net".to_string(),
+                version: Some("v0.20.0".to_string()),
+                replacement: Replacement::FilePath("../net".to_string()),
+                comments: Comments::default(),
+            },
+            ModuleReplacement {
+                module_path: "golang.org/x/text".to_string(),
+                version: None,
+                replacement: Replacement::Module(Module {
+                    module_path: "example.com/fork/text".to_string(),
+                    version: "v0.14.1".to_string(),
+                }),
+                comments: Comments::default(),
+            },
+        ],
+        retract: vec![
+            ModuleRetract {
+                interval: VersionInterval::Single("v1.0.0".to_string()),
+                rationale: Some("published accidentally".to_string()),
+            },
+            ModuleRetract {
+                interval: VersionInterval::Range("v1.1.0".to_string(), "v1.1.5".to_string()),
+                rationale: None,
+            },
+        ],
+        ..Default::default()
+    };
+
+    let rendered = gomod.to_string();
+
+    assert_eq!(
+        rendered,
+        indoc! {r"
+        // Generated service
+        //
+        // Deprecated: use github.com/example/service/v2
+        module github.com/example/service
+
+        go 1.22
+
+        toolchain go1.22.1
+
+        godebug (
+        	default=go1.21
+        	panicnil=1
+        )
+
+        require (
+        	golang.org/x/net v0.20.0
+        	golang.org/x/text v0.14.0 // indirect
+        )
+
+        replace (
+        	golang.org/x/net v0.20.0 => ../net
+        	golang.org/x/text => example.com/fork/text v0.14.1
+        )
+
+        retract (
+        	// published accidentally
+        	v1.0.0
+        	[v1.1.0, v1.1.5]
+        )
+        "}
+    );
+    assert_eq!(GoMod::from_str(&rendered).unwrap(), gomod);
+}
+
+#[test]
+fn test_display_dependency_comments() {
+    let mut dependency = ModuleDependency {
+        module: Module {
+            module_path: "golang.org/x/net".to_string(),
+            version: "v0.20.0".to_string(),
+        },
+        indirect: true,
+        comments: Comments {
+            before: vec!["not rendered".to_string()],
+            suffix: Some("pinned".to_string()),
+        },
+    };
+
+    assert_eq!(
+        dependency.to_string(),
+        "golang.org/x/net v0.20.0 // indirect; pinned"
+    );
+
+    dependency.indirect = false;
+    assert_eq!(dependency.to_string(), "golang.org/x/net v0.20.0 // pinned");
+
+    dependency.comments.suffix = None;
+    assert_eq!(dependency.to_string(), "golang.org/x/net v0.20.0");
+}
+
+#[test]
+fn test_display_quotes_values() {
+    let gomod = GoMod {
+        module: "example.com/app".to_string(),
+        replace: vec![ModuleReplacement {
+            module_path: "example.com/a".to_string(),
+            version: None,
+            replacement: Replacement::FilePath("../my dir/a \"b\"".to_string()),
+            comments: Comments::default(),
+        }],
+        ..Default::default()
+    };
+
+    let rendered = gomod.to_string();
+
+    assert_eq!(
+        rendered,
+        "module example.com/app\n\nreplace example.com/a => \"../my dir/a \\\"b\\\"\"\n"
+    );
+    assert_eq!(GoMod::from_str(&rendered).unwrap(), gomod);
+}
+
+#[test]
+fn test_display_unknown_directives() {
+    let gomod = GoMod {
+        module: "example.com/app".to_string(),
+        unknown: vec![
+            UnknownDirective {
+                keyword: "frobnicate".to_string(),
+                args: vec!["all the".to_string(), "things".to_string()],
+                block: None,
+            },
+            UnknownDirective {
+                keyword: "sandbox".to_string(),
+                args: Vec::new(),
+                block: Some(vec![
+                    vec!["net".to_string(), "off".to_string()],
+                    vec!["fs".to_string(), "read-only".to_string()],
+                ]),
+            },
+            UnknownDirective {
+                keyword: "foo".to_string(),
+                args: vec!["a".to_string()],
+                block: Some(vec![vec!["b".to_string(), "c".to_string()]]),
+            },
+            UnknownDirective {
+                keyword: "bar".to_string(),
+                args: Vec::new(),
+                block: Some(Vec::new()),
+            },
+        ],
+        ..Default::default()
+    };
+
+    let rendered = gomod.to_string();
+
+    assert_eq!(
+        rendered,
+        indoc! {r#"
+        module example.com/app
+
+        frobnicate "all the" things
+
+        sandbox (
+        	net off
+        	fs read-only
+        )
+
+        foo a (
+        	b c
+        )
+
+        bar (
+        )
+        "#}
+    );
+    assert_eq!(GoMod::from_str(&rendered).unwrap(), gomod);
+    assert_eq!(
+        gomod.unknown[0].to_string(),
+        "frobnicate \"all the\" things\n"
+    );
+}
@@ -0,0 +1,75 @@
+use gomod_parser::{GoMod, GoVersionError};
+use std::str::FromStr;
+
+fn error(directive: &str, required: &str, go: &str) -> GoVersionError {
+    GoVersionError {
+        directive: directive.to_string(),
+        required: required.to_string(),
+        go: go.to_string(),
+    }
+}
+
+#[test]
+fn test_check_go_version() {
+    let input = r#"
+module example.com/app
+
+go 1.22.3
+
+toolchain go1.23.0
+
+godebug default=go1.21
+
+tool golang.org/x/tools/cmd/stringer
+
+ignore ./testdata
+
+retract v1.0.0
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(
+        go_mod.check_go_version(),
+        vec![
+            error("godebug", "1.23", "1.22.3"),
+            error("tool", "1.24", "1.22.3"),
+            error("ignore", "1.25", "1.22.3"),
+        ]
+    );
+    assert_eq!(go_mod.min_go_version(), Some("1.25"));
+}
+
+#[test]
+fn test_check_go_version_supported() {
+    for go in ["1.25", "1.25.0", "1.25rc1", "1.26", "2.0"] {
+        let input = format!("module example.com/app\n\ngo {go}\n\ntool example.com/app/cmd/gen\n\nignore ./testdata\n");
+
+        let go_mod = GoMod::from_str(&input).unwrap();
+
+        assert!(go_mod.check_go_version().is_empty(), "{go}");
+    }
+}
+
+#[test]
+fn test_check_go_version_default() {
+    let go_mod = GoMod::from_str("module example.com/app\n\nretract v1.0.0\n").unwrap();
+
+    assert!(go_mod.check_go_version().is_empty());
+    assert_eq!(go_mod.min_go_version(), Some("1.16"));
+
+    let go_mod = GoMod::from_str("module example.com/app\n\ntoolchain go1.21.0\n").unwrap();
+
+    assert_eq!(
+        go_mod.check_go_version(),
+        vec![error("toolchain", "1.21", "1.16")]
+    );
+}
+
+#[test]
+fn test_min_go_version_without_gated_directives() {
+    let go_mod = GoMod::from_str("module example.com/app\n\ngo 1.12\n").unwrap();
+
+    assert!(go_mod.check_go_version().is_empty());
+    assert_eq!(go_mod.min_go_version(), None);
+}
@@ -0,0 +1,111 @@
+use gomod_parser::{GoSum, GoSumEntry};
+use indoc::indoc;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_parse_gosum() {
+    let content = get_test_file_content("compress.sum");
+
+    let go_sum = GoSum::from_str(&content).unwrap();
+
+    assert_eq!(go_sum.entries.len(), 5);
+    assert_eq!(
+        go_sum.entries[2],
+        GoSumEntry {
+            module_path: "github.com/klauspost/cpuid/v2".to_string(),
+            version: "v2.2.6".to_string(),
+            go_mod: false,
+            hash: "h1:ndNyv040zDGIDh8thGkXYjnFtiN02M1PVVF+JE/48xc=".to_string(),
+        }
+    );
+    assert_eq!(
+        go_sum.entries[4],
+        GoSumEntry {
+            module_path: "golang.org/x/sys".to_string(),
+            version: "v0.5.0".to_string(),
+            go_mod: true,
+            hash: "h1:oPkhp1MJrh7nUepCBck5+mAzfO9JrbApNNgaTdGDITg=".to_string(),
+        }
+    );
+    assert_eq!(go_sum.to_string(), content);
+}
+
+#[test]
+fn test_parse_gosum_blank_lines_and_crlf() {
+    let input = "\r\ngolang.org/x/net v0.20.0 h1:abc=\r\n\r\n  golang.org/x/text v0.14.0/go.mod h1:def=  \r\n";
+
+    let go_sum = GoSum::from_str(input).unwrap();
+
+    assert_eq!(
+        go_sum
+            .entries
+            .iter()
+            .map(|e| (e.module_path.as_str(), e.go_mod, e.hash.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("golang.org/x/net", false, "h1:abc="),
+            ("golang.org/x/text", true, "h1:def=")
+        ]
+    );
+}
+
+#[test]
+fn test_parse_gosum_error() {
+    let input = indoc! {r"
+    golang.org/x/net v0.20.0 h1:abc=
+    golang.org/x/text v0.14.0 sha256:def
+    "};
+
+    let err = GoSum::from_str(input).unwrap_err();
+
+    assert_eq!((err.line, err.column), (2, 27));
+    assert_eq!(err.directive, Some("checksum".to_string()));
+    assert_eq!(err.expected, vec!["`h1:` hash".to_string()]);
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 27: invalid checksum, expected `h1:` hash, found `sha256:def`"
+    );
+}
+
+#[test]
+fn test_parse_gosum_with_diagnostics() {
+    let input = indoc! {r"
+    golang.org/x/net v0.20.0 h1:abc=
+    golang.org/x/text
+    golang.org/x/sys v0.16.0 h1:ghi= extra
+    golang.org/x/tools v0.17.0/go.mod h1:jkl=
+    "};
+
+    let (go_sum, diagnostics) = GoSum::parse_with_diagnostics(input);
+
+    assert_eq!(
+        go_sum
+            .entries
+            .iter()
+            .map(|e| e.module_path.as_str())
+            .collect::<Vec<_>>(),
+        vec!["golang.org/x/net", "golang.org/x/tools"]
+    );
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| (d.line, d.expected.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (2, vec!["version".to_string()]),
+            (3, vec!["newline".to_string()])
+        ]
+    );
+}
@@ -0,0 +1,85 @@
+use gomod_parser::{Comments, GoWork, ModuleReplacement, Replacement};
+use indoc::indoc;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_parse_gowork() {
+    let content = get_test_file_content("workspace.work");
+
+    let go_work = GoWork::from_str(&content).unwrap();
+
+    assert_eq!(
+        go_work,
+        GoWork {
+            comment: vec!["Workspace of the example services".to_string()],
+            go: Some("1.23.0".to_string()),
+            toolchain: Some("go1.23.4".to_string()),
+            godebug: HashMap::from([("default".to_string(), "go1.21".to_string())]),
+            uses: vec![
+                "./api".to_string(),
+                "./cmd/server".to_string(),
+                "./internal/tools".to_string(),
+                "./docs".to_string(),
+            ],
+            replace: vec![ModuleReplacement {
+                module_path: "golang.org/x/net".to_string(),
+                version: Some("v0.20.0".to_string()),
+                replacement: Replacement::FilePath("./third_party/net".to_string()),
+                comments: Comments::default(),
+            }],
+        }
+    );
+}
+
+#[test]
+fn test_parse_gowork_rejects_gomod_directives() {
+    let input = indoc! {r"
+    go 1.22
+
+    require golang.org/x/net v0.20.0
+    "};
+
+    let err = GoWork::from_str(input).unwrap_err();
+
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(err.expected, vec!["directive".to_string()]);
+}
+
+#[test]
+fn test_parse_gowork_with_diagnostics() {
+    let input = indoc! {r"
+    go 1.22
+
+    module github.com/example
+
+    use (
+        ./api
+        ./cmd ./extra
+    )
+    "};
+
+    let (go_work, diagnostics) = GoWork::parse_with_diagnostics(input);
+
+    assert_eq!(go_work.go, Some("1.22".to_string()));
+    assert_eq!(go_work.uses, vec!["./api".to_string()]);
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| (d.line, d.directive.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![(3, None), (7, Some("use block"))]
+    );
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "json")]
+
+use gomod_parser::{GoMod, ModuleRetract, Replacement, VersionInterval};
+use indoc::indoc;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_to_go_json() {
+    let gomod = GoMod::from_str(indoc! {r"
+    module github.com/example
+
+    go 1.21
+
+    toolchain go1.21.1
+
+    godebug (
+        panicnil=1
+        default=go1.21
+    )
+
+    require (
+        golang.org/x/net v0.20.0 // indirect
+        golang.org/x/text v0.14.0
+    )
+
+    exclude golang.org/x/net v0.19.1
+
+    replace (
+        golang.org/x/net v0.19.0 => example.com/fork/net v0.19.1
+        golang.org/x/text => ../text
+    )
+
+    retract (
+        v1.0.0
+        [v1.1.0, v1.1.5]
+    )
+    "})
+    .unwrap();
+
+    assert_eq!(
+        gomod.to_go_json(),
+        [
+            "{",
+            "\t\"Module\": {",
+            "\t\t\"Path\": \"github.com/example\"",
+            "\t},",
+            "\t\"Go\": \"1.21\",",
+            "\t\"Toolchain\": \"go1.21.1\",",
+            "\t\"Godebug\": [",
+            "\t\t{",
+            "\t\t\t\"Key\": \"default\",",
+            "\t\t\t\"Value\": \"go1.21\"",
+            "\t\t},",
+            "\t\t{",
+            "\t\t\t\"Key\": \"panicnil\",",
+            "\t\t\t\"Value\": \"1\"",
+            "\t\t}",
+            "\t],",
+            "\t\"Require\": [",
+            "\t\t{",
+            "\t\t\t\"Path\": \"golang.org/x/net\",",
+            "\t\t\t\"Version\": \"v0.20.0\",",
+            "\t\t\t\"Indirect\": true",
+            "\t\t},",
+            "\t\t{",
+            "\t\t\t\"Path\": \"golang.org/x/text\",",
+            "\t\t\t\"Version\": \"v0.14.0\"",
+            "\t\t}",
+            "\t],",
+            "\t\"Exclude\": [",
+            "\t\t{",
+            "\t\t\t\"Path\": \"golang.org/x/net\",",
+            "\t\t\t\"Version\": \"v0.19.1\"",
+            "\t\t}",
+            "\t],",
+            "\t\"Replace\": [",
+            "\t\t{",
+            "\t\t\t\"Old\": {",
+            "\t\t\t\t\"Path\": \"golang.org/x/net\",",
+            "\t\t\t\t\"Version\": \"v0.19.0\"",
+            "\t\t\t},",
+            "\t\t\t\"New\": {",
+            "\t\t\t\t\"Path\": \"example.com/fork/net\",",
+            "\t\t\t\t\"Version\": \"v0.19.1\"",
+            "\t\t\t}",
+            "\t\t},",
+            "\t\t{",
+            "\t\t\t\"Old\": {",
+            "\t\t\t\t\"Path\": \"golang.org/x/text\"",
+            "\t\t\t},",
+            "\t\t\t\"New\": {",
+            "\t\t\t\t\"Path\": \"../text\"",
+            "\t\t\t}",
+            "\t\t}",
+            "\t],",
+            "\t\"Retract\": [",
+            "\t\t{",
+            "\t\t\t\"Low\": \"v1.0.0\",",
+            "\t\t\t\"High\": \"v1.0.0\"",
+            "\t\t},",
+            "\t\t{",
+            "\t\t\t\"Low\": \"v1.1.0\",",
+            "\t\t\t\"High\": \"v1.1.5\"",
+            "\t\t}",
+            "\t],",
+            "\t\"Tool\": null,",
+            "\t\"Ignore\": null",
+            "}",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_from_go_json() {
+    let json = r#"{
+        "Module": {"Path": "github.com/example"},
+        "Go": "1.24",
+        "Require": [{"Path": "golang.org/x/net", "Version": "v0.20.0", "Indirect": true}],
+        "Exclude": null,
+        "Replace": [{"Old": {"Path": "golang.org/x/text"}, "New": {"Path": "../text"}}],
+        "Retract": [{"Low": "v1.1.0", "High": "v1.1.5", "Rationale": "broken"}],
+        "Tool": [{"Path": "golang.org/x/tools/cmd/stringer"}]
+    }"#;
+
+    let gomod = GoMod::from_go_json(json).unwrap();
+
+    assert_eq!(gomod.module, "github.com/example".to_string());
+    assert_eq!(gomod.go, Some("1.24".to_string()));
+    assert_eq!(gomod.toolchain, None);
+    assert!(gomod.require[0].indirect);
+    assert!(gomod.exclude.is_empty());
+    assert_eq!(gomod.replace[0].version, None);
+    assert_eq!(
+        gomod.replace[0].replacement,
+        Replacement::FilePath("../text".to_string())
+    );
+    assert_eq!(
+        gomod.retract,
+        vec![ModuleRetract {
+            interval: VersionInterval::Range("v1.1.0".to_string(), "v1.1.5".to_string()),
+            rationale: Some("broken".to_string()),
+        }]
+    );
+    assert_eq!(gomod.tool.len(), 1);
+    assert_eq!(gomod.tool[0].path, "golang.org/x/tools/cmd/stringer");
+    assert!(gomod.ignore.is_empty());
+}
+
+#[test]
+fn test_go_json_roundtrip() {
+    for file_name in ["godebug.mod", "ignore.mod", "kubernetes.mod", "tool.mod"] {
+        let gomod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+
+        let roundtrip = GoMod::from_go_json(&gomod.to_go_json()).unwrap();
+
+        // comments are not part of the schema
+        assert_eq!(
+            roundtrip,
+            GoMod {
+                comment: Vec::new(),
+                ..gomod
+            },
+            "{file_name}"
+        );
+    }
+}
+
+#[test]
+fn test_from_go_json_invalid() {
+    assert!(GoMod::from_go_json(r#"{"Go": "1.21"}"#).is_err());
+}
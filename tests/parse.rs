@@ -1,4 +1,7 @@
-use gomod_parser::GoMod;
+use gomod_parser::{
+    GoMod, Module, ModuleRetract, ParseMode, PathEntry, Replacement, UnknownDirective,
+    VersionInterval,
+};
 use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -14,12 +17,33 @@ fn get_test_file_content(file_name: &str) -> String {
     read_to_string(path).unwrap()
 }
 
+fn paths(entries: &[PathEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.path.as_str()).collect()
+}
+
 #[test]
 fn test_parse_on_compress() {
     let file_content = get_test_file_content("compress.mod");
     let gomod = file_content.parse::<GoMod>().unwrap();
 
     assert_eq!(gomod.module, "github.com/klauspost/compress".to_string());
+    assert_eq!(
+        gomod.retract,
+        vec![
+            ModuleRetract {
+                interval: VersionInterval::Single("v1.14.3".to_string()),
+                rationale: Some("https://github.com/klauspost/compress/pull/503".to_string()),
+            },
+            ModuleRetract {
+                interval: VersionInterval::Single("v1.14.2".to_string()),
+                rationale: None,
+            },
+            ModuleRetract {
+                interval: VersionInterval::Single("v1.14.1".to_string()),
+                rationale: None,
+            },
+        ]
+    );
 }
 
 #[test]
@@ -62,6 +86,27 @@ fn test_parse_on_prometheus() {
     assert_eq!(gomod.module, "github.com/prometheus/prometheus".to_string());
 }
 
+#[test]
+fn test_parse_with_diagnostics_on_kubernetes() {
+    let file_content = get_test_file_content("kubernetes.mod");
+    let expected = file_content.parse::<GoMod>().unwrap();
+
+    let broken_content = file_content.replacen(
+        "github.com/google/go-cmp v0.6.0",
+        "github.com/google/go-cmp",
+        1,
+    );
+    assert!(broken_content.parse::<GoMod>().is_err());
+
+    let (gomod, diagnostics) = GoMod::parse_with_diagnostics(&broken_content);
+
+    assert_eq!(gomod.module, "k8s.io/kubernetes".to_string());
+    assert_eq!(gomod.require.len(), expected.require.len() - 1);
+    assert_eq!(gomod.replace, expected.replace);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].directive, Some("require block".to_string()));
+}
+
 #[test]
 fn test_parse_godebug() {
     let file_content = get_test_file_content("godebug.mod");
@@ -83,7 +128,7 @@ fn test_parse_tool() {
     let gomod = file_content.parse::<GoMod>().unwrap();
 
     assert_eq!(
-        gomod.tool,
+        paths(&gomod.tool),
         vec![
             "example.com/mymodule/cmd/mytool1",
             "example.com/mymodule/cmd/mytool2",
@@ -101,13 +146,8 @@ fn test_parse_ignore() {
 
     assert_eq!(gomod.module, "github.com/example/ignore".to_string());
     assert_eq!(
-        gomod.ignore,
-        vec![
-            "./build".to_string(),
-            "./testdata".to_string(),
-            "./vendor/temp".to_string(),
-            "./node_modules".to_string(),
-        ]
+        paths(&gomod.ignore),
+        vec!["./build", "./testdata", "./vendor/temp", "./node_modules"]
     );
 }
 
@@ -129,7 +169,7 @@ fn test_no_trailing_newline() {
     let gomod = file_content.parse::<GoMod>().unwrap();
 
     assert_eq!(
-        gomod.tool,
+        paths(&gomod.tool),
         vec![
             "example.com/mymodule/cmd/mytool1",
             "example.com/mymodule/cmd/mytool2",
@@ -139,3 +179,231 @@ fn test_no_trailing_newline() {
         ]
     );
 }
+
+#[test]
+fn test_parse_quoted_strings() {
+    let input = r#"module "example.com/quoted"
+
+require "golang.org/x/net" `v0.20.0` // indirect
+
+replace (
+	example.com/a => "../my dir/a" // local
+	`example.com/b` "v1.0.0" => "example.com/fork/b" "v1.0.1"
+	example.com/c => "../tab\tand \"quote\" é"
+)
+
+retract ["v1.0.0", `v1.0.5`]
+
+tool "example.com/my tools/cmd/gen"
+"#;
+
+    let gomod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(gomod.module, "example.com/quoted");
+    assert_eq!(gomod.require[0].module.module_path, "golang.org/x/net");
+    assert_eq!(gomod.require[0].module.version, "v0.20.0");
+    assert!(gomod.require[0].indirect);
+    assert_eq!(
+        gomod.replace[0].replacement,
+        Replacement::FilePath("../my dir/a".to_string())
+    );
+    assert_eq!(gomod.replace[0].comments.suffix, Some("local".to_string()));
+    assert_eq!(gomod.replace[1].module_path, "example.com/b");
+    assert_eq!(gomod.replace[1].version, Some("v1.0.0".to_string()));
+    assert_eq!(
+        gomod.replace[1].replacement,
+        Replacement::Module(Module {
+            module_path: "example.com/fork/b".to_string(),
+            version: "v1.0.1".to_string(),
+        })
+    );
+    assert_eq!(
+        gomod.replace[2].replacement,
+        Replacement::FilePath("../tab\tand \"quote\" é".to_string())
+    );
+    assert_eq!(
+        gomod.retract[0].interval,
+        VersionInterval::Range("v1.0.0".to_string(), "v1.0.5".to_string())
+    );
+    assert_eq!(paths(&gomod.tool), vec!["example.com/my tools/cmd/gen"]);
+}
+
+#[test]
+fn test_parse_invalid_quoted_strings() {
+    for (input, column) in [
+        (
+            "module example.com/a\n\nreplace example.com/b => \"../b\\q\"\n",
+            26,
+        ),
+        (
+            "module example.com/a\n\nreplace example.com/b => \"../b\\x4\"\n",
+            26,
+        ),
+        (
+            "module example.com/a\n\nrequire example.com/b \"v1.0.0\n",
+            30,
+        ),
+    ] {
+        let err = GoMod::from_str(input).unwrap_err();
+
+        assert_eq!((err.line, err.column), (3, column), "{input}");
+    }
+}
+
+#[test]
+fn test_parse_lax_mode() {
+    let input = r#"module example.com/dependency
+
+go 1.30
+
+toolchain go1.30.0 extra
+
+frobnicate all the things
+
+frobnicate ( // not yet supported
+	everything
+)
+
+foo.bar baz
+
+require golang.org/x/net v0.20.0
+
+exclude golang.org/x/net v0.19.0
+
+replace golang.org/x/net => ../net
+
+tool golang.org/x/tools/cmd/stringer
+
+retract v1.0.0
+"#;
+
+    assert!(GoMod::from_str(input).is_err());
+    assert!(GoMod::parse_with_mode(input, ParseMode::Strict).is_err());
+
+    let gomod = GoMod::parse_with_mode(input, ParseMode::Lax).unwrap();
+
+    assert_eq!(gomod.module, "example.com/dependency");
+    assert_eq!(gomod.go, Some("1.30".to_string()));
+    assert_eq!(gomod.require[0].module.module_path, "golang.org/x/net");
+    assert_eq!(gomod.exclude[0].module.version, "v0.19.0");
+    assert_eq!(
+        gomod.retract[0].interval,
+        VersionInterval::Single("v1.0.0".to_string())
+    );
+    assert_eq!(gomod.toolchain, None);
+    assert_eq!(
+        gomod
+            .unknown
+            .iter()
+            .map(|unknown| unknown.keyword.as_str())
+            .collect::<Vec<_>>(),
+        vec!["frobnicate", "frobnicate", "foo.bar"]
+    );
+    assert!(gomod.replace.is_empty());
+    assert!(gomod.tool.is_empty());
+    assert_eq!(gomod.syntax.to_string(), input);
+}
+
+#[test]
+fn test_parse_lax_mode_invalid_directive() {
+    let input = "module example.com/dependency\n\nfrobnicate\n\nrequire golang.org/x/net\n";
+
+    let err = GoMod::parse_with_mode(input, ParseMode::Lax).unwrap_err();
+
+    assert_eq!(err.line, 5);
+    assert_eq!(err.directive, Some("require".to_string()));
+}
+
+#[test]
+fn test_parse_unknown_directives() {
+    let input = r#"module example.com/service
+
+go 1.40
+
+frobnicate "all the" things // since go1.40
+
+require golang.org/x/net v0.20.0
+
+frobnicate example.com/a (
+	// first
+	v1.0.0 => [v1.0.1, v1.0.2]
+
+	`raw value`
+)
+"#;
+
+    let gomod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(gomod.require[0].module.module_path, "golang.org/x/net");
+    assert_eq!(
+        gomod.unknown,
+        vec![
+            UnknownDirective {
+                keyword: "frobnicate".to_string(),
+                args: vec!["all the".to_string(), "things".to_string()],
+                block: None,
+            },
+            UnknownDirective {
+                keyword: "frobnicate".to_string(),
+                args: vec!["example.com/a".to_string()],
+                block: Some(vec![
+                    vec!["v1.0.0", "=>", "[", "v1.0.1", ",", "v1.0.2", "]"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                    vec!["raw value".to_string()],
+                ]),
+            },
+        ]
+    );
+    assert_eq!(
+        gomod.comment,
+        vec!["since go1.40".to_string(), "first".to_string()]
+    );
+    assert_eq!(gomod.syntax.to_string(), input);
+    assert!(GoMod::from_str(
+        "module example.com/a
+
+frob (
+	x
+"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_parse_incomplete_directives() {
+    for input in [
+        "module x.com/a\n\nrequire\n",
+        "module x.com/a\n\ngo\n",
+        "module\n",
+        "module x.com/a\n\nrequire a.com/b v1.0.0 junk\n",
+        "module x.com/a\n\nexclude a.com/b v1.0.0 junk\n",
+        "module x.com/a\n\nreplace a.com/b => a.com/c v1.0.0 junk\n",
+        "module x.com/a\n\nretract v1.0.0 junk\n",
+        "module x.com/a\n\nretract [v1.0.0, v1.0.5] junk\n",
+        "module x.com/a\n\ntool a.com/b/cmd/c junk\n",
+        "module x.com/a\n\nignore ./testdata junk\n",
+        "module x.com/a\n\nrequire (\n\ta.com/b v1.0.0 junk\n)\n",
+    ] {
+        assert!(GoMod::from_str(input).is_err(), "{input}");
+
+        let (gomod, diagnostics) = GoMod::parse_with_diagnostics(input);
+
+        assert!(gomod.unknown.is_empty(), "{input}");
+        assert_eq!(diagnostics.len(), 1, "{input}");
+    }
+}
+
+#[test]
+fn test_parse_quoted_go_version() {
+    for (input, expected) in [
+        ("module x.com/a\n\ngo \"1.21\"\n", "go version"),
+        ("module x.com/a\n\ntoolchain `go1.21.0`\n", "toolchain name"),
+    ] {
+        let err = GoMod::from_str(input).unwrap_err();
+
+        assert_eq!(err.line, 3, "{input}");
+        assert_eq!(err.expected, vec![expected.to_string()], "{input}");
+    }
+}
@@ -0,0 +1,360 @@
+use gomod_parser::path::{check_import_path, check_major_version, check_path, split_path_version};
+use gomod_parser::{GoMod, Module};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+fn reason(path: &str) -> String {
+    check_path(path).unwrap_err().reason
+}
+
+#[test]
+fn test_check_path_valid() {
+    for path in [
+        "github.com/example/project",
+        "github.com/example/project/v2",
+        "golang.org/x/net",
+        "gopkg.in/yaml.v3",
+        "gopkg.in/check.v1",
+        "gopkg.in/yaml.v0",
+        "example.com/Mixed_Case~name",
+        "example.com/x/con2",
+    ] {
+        assert!(check_path(path).is_ok(), "{path}");
+    }
+}
+
+#[test]
+fn test_check_path_invalid() {
+    assert_eq!(reason(""), "empty string");
+    assert_eq!(reason("-example.com/x"), "leading dash");
+    assert_eq!(reason("example.com//x"), "double slash");
+    assert_eq!(reason("example.com/x/"), "trailing slash");
+    assert_eq!(reason("/example.com"), "empty path element");
+    assert_eq!(reason("example.com/.x"), "leading dot in path element");
+    assert_eq!(reason("example.com/x."), "trailing dot in path element");
+    assert_eq!(reason("example.com/.."), "invalid path element \"..\"");
+    assert_eq!(reason("example.com/a b"), "invalid char ' '");
+    assert_eq!(reason("example.com/é"), "invalid char 'é'");
+    assert_eq!(
+        reason("example.com/aux.txt"),
+        "\"aux\" disallowed as path element component on Windows"
+    );
+    assert_eq!(
+        reason("example.com/PROGRA~1"),
+        "trailing tilde and digits in path element"
+    );
+    assert_eq!(
+        reason("example/project"),
+        "missing dot in first path element"
+    );
+    assert_eq!(
+        reason("GitHub.com/example"),
+        "invalid char 'G' in first path element"
+    );
+    assert_eq!(reason("example.com/x/v1"), "invalid version");
+    assert_eq!(reason("example.com/x/v02"), "invalid version");
+    assert_eq!(reason("gopkg.in/yaml"), "invalid version");
+}
+
+#[test]
+fn test_check_import_path() {
+    assert!(check_import_path("example").is_ok());
+    assert!(check_import_path("example.com/x/.hidden").is_ok());
+    assert!(check_import_path("example.com/c++").is_ok());
+    assert!(check_path("example.com/c++").is_err());
+    assert!(check_import_path("example.com/x/../y").is_err());
+}
+
+#[test]
+fn test_split_path_version() {
+    assert_eq!(
+        split_path_version("github.com/example/project"),
+        Some(("github.com/example/project", ""))
+    );
+    assert_eq!(
+        split_path_version("github.com/example/project/v2"),
+        Some(("github.com/example/project", "/v2"))
+    );
+    assert_eq!(
+        split_path_version("gopkg.in/yaml.v3"),
+        Some(("gopkg.in/yaml", ".v3"))
+    );
+    assert_eq!(
+        split_path_version("gopkg.in/yaml.v3-unstable"),
+        Some(("gopkg.in/yaml", ".v3-unstable"))
+    );
+    assert_eq!(split_path_version("github.com/example/v1"), None);
+    assert_eq!(split_path_version("github.com/example/v2.1"), None);
+    assert_eq!(split_path_version("gopkg.in/yaml.v03"), None);
+}
+
+#[test]
+fn test_check_paths() {
+    let input = r#"
+module example
+
+require (
+	golang.org/x/net v0.20.0
+	Example.com/x v1.0.0
+)
+
+exclude golang.org/x/net/v1 v1.0.0
+
+replace example.com/old => example.com/new/ v1.0.0
+
+replace example.com/local => ../local
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+    let errors = go_mod.check_paths();
+
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            (
+                e.directive.as_deref().unwrap(),
+                e.path.as_str(),
+                e.reason.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "require",
+                "Example.com/x",
+                "invalid char 'E' in first path element"
+            ),
+            ("exclude", "golang.org/x/net/v1", "invalid version"),
+            ("replace", "example.com/new/", "trailing slash"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_paths_fixtures() {
+    for file_name in [
+        "compress.mod",
+        "docker_docs.mod",
+        "godebug.mod",
+        "ignore.mod",
+        "iris.mod",
+        "kubernetes.mod",
+        "prometheus.mod",
+        "tool.mod",
+    ] {
+        let go_mod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+        assert_eq!(go_mod.check_paths(), vec![], "{file_name}");
+    }
+}
+
+fn major_reason(path: &str, version: &str) -> String {
+    check_major_version(path, version).unwrap_err().reason
+}
+
+#[test]
+fn test_check_major_version() {
+    for (path, version) in [
+        ("github.com/example/project", "v0.1.0"),
+        ("github.com/example/project", "v1.2.3"),
+        ("github.com/example/project", "v2.0.0+incompatible"),
+        ("github.com/example/project/v2", "v2.1.0"),
+        (
+            "github.com/example/project/v3",
+            "v3.0.0-20240102150405-abcdef123456",
+        ),
+        ("gopkg.in/yaml.v3", "v3.0.1"),
+        ("gopkg.in/yaml.v0", "v0.1.0"),
+        ("gopkg.in/check.v1", "v0.0.0-20161208181325-20d25e280405"),
+        ("gopkg.in/yaml.v3-unstable", "v1.0.0"),
+    ] {
+        assert!(
+            check_major_version(path, version).is_ok(),
+            "{path}@{version}"
+        );
+    }
+}
+
+#[test]
+fn test_check_major_version_invalid() {
+    assert_eq!(
+        major_reason("github.com/example/project", "v2.0.0"),
+        "should be v0 or v1, not v2"
+    );
+    assert_eq!(
+        major_reason("github.com/example/project/v2", "v1.0.0"),
+        "should be v2, not v1"
+    );
+    assert_eq!(
+        major_reason("github.com/example/project/v2", "v3.0.0"),
+        "should be v2, not v3"
+    );
+    assert_eq!(
+        major_reason("gopkg.in/yaml.v2", "v3.0.0"),
+        "should be v2, not v3"
+    );
+    assert_eq!(
+        major_reason("github.com/example/project/v2", "v2.0.0+incompatible"),
+        "+incompatible not allowed with major version suffix"
+    );
+    assert_eq!(
+        major_reason("github.com/example/project", "v1.0.0+incompatible"),
+        "+incompatible not allowed on v1"
+    );
+    assert_eq!(
+        major_reason("github.com/example/project", "latest"),
+        "not a semantic version"
+    );
+
+    let err = check_major_version("github.com/example/project/v1", "v1.0.0").unwrap_err();
+    assert_eq!(err.version, None);
+    assert_eq!(err.reason, "invalid version");
+}
+
+#[test]
+fn test_split_module_path() {
+    let module = Module {
+        module_path: "github.com/example/project/v2".to_string(),
+        version: "v2.0.0".to_string(),
+    };
+
+    assert_eq!(
+        module.split_path(),
+        Some(("github.com/example/project", "/v2"))
+    );
+    assert!(module.check_major_version().is_ok());
+}
+
+#[test]
+fn test_check_major_versions() {
+    let input = r#"
+module example.com/app
+
+require (
+	github.com/example/a/v2 v2.1.0
+	github.com/example/b v2.0.0
+	github.com/example/c/v3 v3.0.0+incompatible
+)
+
+exclude github.com/example/d/v2 v1.0.0
+
+replace github.com/example/e v1.0.0 => github.com/fork/e/v2 v2.0.0
+
+replace github.com/example/f v2.0.0 => ../f
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+    let errors = go_mod.check_major_versions();
+
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            (
+                e.directive.as_deref().unwrap(),
+                e.path.as_str(),
+                e.version.as_deref().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            ("require", "github.com/example/b", "v2.0.0"),
+            ("require", "github.com/example/c/v3", "v3.0.0+incompatible"),
+            ("exclude", "github.com/example/d/v2", "v1.0.0"),
+            ("replace", "github.com/example/f", "v2.0.0"),
+        ]
+    );
+}
+
+#[test]
+fn test_check_major_versions_fixtures() {
+    for file_name in [
+        "compress.mod",
+        "docker_docs.mod",
+        "godebug.mod",
+        "ignore.mod",
+        "iris.mod",
+        "kubernetes.mod",
+        "prometheus.mod",
+        "tool.mod",
+    ] {
+        let go_mod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+        assert_eq!(go_mod.check_major_versions(), vec![], "{file_name}");
+    }
+}
+
+fn module(module_path: &str, version: &str) -> Module {
+    Module {
+        module_path: module_path.to_string(),
+        version: version.to_string(),
+    }
+}
+
+#[test]
+fn test_escape() {
+    let m = module("github.com/BurntSushi/TOML", "v1.0.0-RC1");
+
+    assert_eq!(
+        m.escape_path().unwrap(),
+        "github.com/!burnt!sushi/!t!o!m!l".to_string()
+    );
+    assert_eq!(m.escape_version().unwrap(), "v1.0.0-!r!c1".to_string());
+    assert_eq!(
+        Module::unescape_path("github.com/!burnt!sushi/!t!o!m!l").unwrap(),
+        m.module_path
+    );
+    assert_eq!(
+        module("golang.org/x/net", "v0.20.0").escape_path().unwrap(),
+        "golang.org/x/net".to_string()
+    );
+}
+
+#[test]
+fn test_escape_invalid() {
+    assert_eq!(
+        module("github.com/a!b", "v1.0.0")
+            .escape_path()
+            .unwrap_err()
+            .reason,
+        "invalid char '!'"
+    );
+    assert_eq!(
+        module("github.com/x", "v1.0.0!")
+            .escape_version()
+            .unwrap_err()
+            .reason,
+        "disallowed version string"
+    );
+    assert_eq!(
+        module("github.com/x", "v1/0")
+            .escape_version()
+            .unwrap_err()
+            .version,
+        Some("v1/0".to_string())
+    );
+
+    for escaped in ["github.com/Azure", "github.com/!", "github.com/!1"] {
+        assert_eq!(
+            Module::unescape_path(escaped).unwrap_err().reason,
+            "invalid escaped module path",
+            "{escaped}"
+        );
+    }
+    assert_eq!(
+        Module::unescape_path("!github.com/x").unwrap_err().reason,
+        "invalid char 'G' in first path element"
+    );
+}
@@ -0,0 +1,95 @@
+use gomod_parser::{GoMod, Module, ModuleRetract, Version, VersionInterval};
+use std::str::FromStr;
+
+fn module(module_path: &str, version: &str) -> Module {
+    Module {
+        module_path: module_path.to_string(),
+        version: version.to_string(),
+    }
+}
+
+fn version(v: &str) -> Version {
+    Version::from_str(v).unwrap()
+}
+
+#[test]
+fn test_is_excluded() {
+    let input = r#"
+module example.com/app
+
+exclude (
+	golang.org/x/net v0.19.0
+	golang.org/x/text v0.14.0
+	golang.org/x/sys v0.16
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert!(go_mod.is_excluded(&module("golang.org/x/net", "v0.19.0")));
+    assert!(go_mod.is_excluded(&module("golang.org/x/text", "v0.14")));
+    assert!(go_mod.is_excluded(&module("golang.org/x/sys", "v0.16.0")));
+    assert!(!go_mod.is_excluded(&module("golang.org/x/net", "v0.20.0")));
+    assert!(!go_mod.is_excluded(&module("golang.org/x/sys", "v0.19.0")));
+}
+
+#[test]
+fn test_is_retracted() {
+    let input = r#"
+module example.com/lib
+
+retract (
+	v1.0.0 // published accidentally
+	[v1.2.0, v1.2.5]
+	[v1.2.5, v1.3.0-rc.1]
+	[v2.0.0, v2.0.0]
+	v3.1
+	invalid
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    for v in [
+        "v1.0.0",
+        "v1.2.0",
+        "v1.2.3-beta",
+        "v1.2.5",
+        "v1.3.0-rc.1",
+        "v2.0.0+incompatible",
+        "v3.1.0",
+    ] {
+        assert!(go_mod.is_retracted(&version(v)), "{v}");
+    }
+    for v in ["v0.9.0", "v1.0.1", "v1.2.0-rc.1", "v1.3.0-rc.2", "v1.3.0"] {
+        assert!(!go_mod.is_retracted(&version(v)), "{v}");
+    }
+}
+
+#[test]
+fn test_retracted_by() {
+    let input = r#"
+module example.com/lib
+
+retract [v1.2.0, v1.2.5]
+
+retract v1.2.5 // broken build
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(
+        go_mod.retracted_by(&version("v1.2.5")),
+        vec![
+            &ModuleRetract {
+                interval: VersionInterval::Range("v1.2.0".to_string(), "v1.2.5".to_string()),
+                rationale: None,
+            },
+            &ModuleRetract {
+                interval: VersionInterval::Single("v1.2.5".to_string()),
+                rationale: Some("broken build".to_string()),
+            }
+        ]
+    );
+    assert!(go_mod.retracted_by(&version("v1.3.0")).is_empty());
+}
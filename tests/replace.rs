@@ -0,0 +1,109 @@
+use gomod_parser::{GoMod, Module, Replacement};
+use std::str::FromStr;
+
+fn module(module_path: &str, version: &str) -> Replacement {
+    Replacement::Module(Module {
+        module_path: module_path.to_string(),
+        version: version.to_string(),
+    })
+}
+
+#[test]
+fn test_effective_module() {
+    let input = r#"
+module example.com/app
+
+replace (
+	golang.org/x/net v0.19.0 => ../net
+	golang.org/x/net => golang.org/x/net v0.21.0
+	golang.org/x/text v0.14.0 => golang.org/x/text v0.13.0
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/net", "v0.19.0"),
+        Ok(Replacement::FilePath("../net".to_string()))
+    );
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/net", "v0.20.0"),
+        Ok(module("golang.org/x/net", "v0.21.0"))
+    );
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/text", "v0.14.0"),
+        Ok(module("golang.org/x/text", "v0.13.0"))
+    );
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/text", "v0.14"),
+        Ok(module("golang.org/x/text", "v0.13.0"))
+    );
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/text", "v0.15.0"),
+        Ok(module("golang.org/x/text", "v0.15.0"))
+    );
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/sys", "v0.16.0"),
+        Ok(module("golang.org/x/sys", "v0.16.0"))
+    );
+}
+
+#[test]
+fn test_effective_module_duplicates() {
+    let input = r#"
+module example.com/app
+
+replace golang.org/x/net => ../net
+
+replace golang.org/x/net => ../net
+
+replace (
+	golang.org/x/text v0.14.0 => ../text
+	golang.org/x/text v0.14.0 => golang.org/x/text v0.13.0
+)
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    assert_eq!(
+        go_mod.effective_module("golang.org/x/net", "v0.20.0"),
+        Ok(Replacement::FilePath("../net".to_string()))
+    );
+
+    let err = go_mod
+        .effective_module("golang.org/x/text", "v0.14.0")
+        .unwrap_err();
+    assert_eq!(err.module_path, "golang.org/x/text".to_string());
+    assert_eq!(err.version, Some("v0.14.0".to_string()));
+    assert_eq!(
+        err.to_string(),
+        "conflicting replacements for golang.org/x/text@v0.14.0\n\t../text\n\tgolang.org/x/text v0.13.0"
+    );
+}
+
+#[test]
+fn test_effective_module_wildcard_conflict() {
+    let input = r#"
+module example.com/app
+
+replace golang.org/x/net => ../net
+
+replace golang.org/x/net => ../net2
+
+replace golang.org/x/net v0.19.0 => ../net3
+"#;
+
+    let go_mod = GoMod::from_str(input).unwrap();
+
+    let err = go_mod
+        .effective_module("golang.org/x/net", "v0.19.0")
+        .unwrap_err();
+    assert_eq!(err.version, None);
+    assert_eq!(
+        err.replacements,
+        vec![
+            Replacement::FilePath("../net".to_string()),
+            Replacement::FilePath("../net2".to_string())
+        ]
+    );
+}
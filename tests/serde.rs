@@ -0,0 +1,124 @@
+#![cfg(feature = "serde")]
+
+use gomod_parser::{
+    Comments, GoMod, GoWork, Module, ModuleReplacement, Replacement, VersionInterval,
+};
+use serde_json::json;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_serde_roundtrip() {
+    for file_name in ["compress.mod", "godebug.mod", "kubernetes.mod", "tool.mod"] {
+        let gomod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+
+        let json = serde_json::to_string(&gomod).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<GoMod>(&json).unwrap(),
+            gomod,
+            "{file_name}"
+        );
+    }
+}
+
+#[test]
+fn test_serde_field_names() {
+    let gomod = GoMod::from_str(indoc::indoc! {r"
+    module github.com/example
+
+    go 1.21
+
+    require golang.org/x/net v0.20.0 // indirect
+
+    // local fork
+    replace golang.org/x/net => ../net
+
+    retract [v1.0.0, v1.0.5]
+    "})
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&gomod).unwrap(),
+        json!({
+            "comment": [],
+            "module": "github.com/example",
+            "deprecated": null,
+            "go": "1.21",
+            "godebug": {},
+            "tool": [],
+            "toolchain": null,
+            "require": [{
+                "module": {"module_path": "golang.org/x/net", "version": "v0.20.0"},
+                "indirect": true,
+                "comments": {"before": [], "suffix": null}
+            }],
+            "exclude": [],
+            "replace": [{
+                "module_path": "golang.org/x/net",
+                "version": null,
+                "replacement": {"file_path": "../net"},
+                "comments": {"before": ["local fork"], "suffix": null}
+            }],
+            "retract": [{
+                "interval": {"range": ["v1.0.0", "v1.0.5"]},
+                "rationale": null
+            }],
+            "ignore": [],
+            "unknown": []
+        })
+    );
+}
+
+#[test]
+fn test_serde_enum_variants() {
+    let replacement = Replacement::Module(Module {
+        module_path: "example.com/fork/net".to_string(),
+        version: "v0.19.1".to_string(),
+    });
+    let retract = VersionInterval::Single("v1.0.0".to_string());
+
+    assert_eq!(
+        serde_json::to_value(&replacement).unwrap(),
+        json!({"module": {"module_path": "example.com/fork/net", "version": "v0.19.1"}})
+    );
+    assert_eq!(
+        serde_json::to_value(&retract).unwrap(),
+        json!({"single": "v1.0.0"})
+    );
+    assert_eq!(
+        serde_json::from_value::<ModuleReplacement>(json!({
+            "module_path": "golang.org/x/net",
+            "version": "v0.19.0",
+            "replacement": {"module": {"module_path": "example.com/fork/net", "version": "v0.19.1"}}
+        }))
+        .unwrap(),
+        ModuleReplacement {
+            module_path: "golang.org/x/net".to_string(),
+            version: Some("v0.19.0".to_string()),
+            replacement,
+            comments: Comments::default(),
+        }
+    );
+}
+
+#[test]
+fn test_serde_go_work_use() {
+    let go_work = GoWork::from_str("go 1.22\n\nuse ./api\n").unwrap();
+
+    let value = serde_json::to_value(&go_work).unwrap();
+
+    assert_eq!(value["use"], json!(["./api"]));
+    assert_eq!(serde_json::from_value::<GoWork>(value).unwrap(), go_work);
+}
@@ -0,0 +1,174 @@
+use gomod_parser::syntax::{FileSyntax, Line, Stmt, Token};
+use indoc::indoc;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_round_trip_on_test_data() {
+    for file_name in [
+        "compress.mod",
+        "docker_docs.mod",
+        "godebug.mod",
+        "ignore.mod",
+        "iris.mod",
+        "kubernetes.mod",
+        "prometheus.mod",
+        "tool.mod",
+    ] {
+        let file_content = get_test_file_content(file_name);
+        let syntax = FileSyntax::from_str(&file_content).unwrap();
+
+        assert_eq!(syntax.to_string(), file_content, "{file_name}");
+    }
+}
+
+#[test]
+fn test_round_trip_with_carriage_return() {
+    let file_content = get_test_file_content("docker_docs.mod")
+        .replace("\r", "") // Replace any current CR
+        .replace("\n", "\r\n"); // Replace LF with CRLF
+
+    let syntax = FileSyntax::from_str(&file_content).unwrap();
+
+    assert_eq!(syntax.to_string(), file_content);
+    assert!(syntax.lines().all(|line| line.newline == "\r\n"));
+}
+
+#[test]
+fn test_round_trip_irregular_whitespace() {
+    let input = "  // leading\t \n\nmodule   github.com/ws  \t\r\nrequire(\n )  \n\t\treplace a=>\"../my dir/a\"//note\n   ";
+
+    let syntax = FileSyntax::from_str(input).unwrap();
+
+    assert_eq!(syntax.to_string(), input);
+}
+
+#[test]
+fn test_lines_and_blocks() {
+    let input = indoc! {r#"
+    // Example
+    module github.com/example
+
+    require (
+        golang.org/x/net v0.20.0 // indirect
+
+        // pinned
+        golang.org/x/text v0.14.0
+    )
+
+    retract [v1.0.0, v1.0.5]
+    "#};
+
+    let syntax = FileSyntax::from_str(input).unwrap();
+
+    assert_eq!(syntax.stmts.len(), 6);
+    assert_eq!(
+        syntax.stmts[0],
+        Stmt::Line(Line {
+            tokens: vec![],
+            comment: Some(Token::new("", "// Example")),
+            trailing: String::new(),
+            newline: "\n".to_string(),
+        })
+    );
+    assert_eq!(
+        syntax.stmts[1],
+        Stmt::Line(Line::new(["module", "github.com/example"]))
+    );
+    assert!(matches!(&syntax.stmts[2], Stmt::Line(line) if line.is_blank()));
+
+    let Stmt::Block(block) = &syntax.stmts[3] else {
+        panic!("expected a block");
+    };
+    assert_eq!(block.verb(), Some("require"));
+    assert_eq!(block.lines.len(), 4);
+    assert_eq!(block.lines[0].indent(), "    ");
+    assert_eq!(
+        block.lines[0].values().collect::<Vec<_>>(),
+        vec!["golang.org/x/net", "v0.20.0"]
+    );
+    assert_eq!(block.lines[0].comment, Some(Token::new(" ", "// indirect")));
+    assert!(block.lines[1].is_blank());
+    assert_eq!(
+        block.lines[2].comment,
+        Some(Token::new("    ", "// pinned"))
+    );
+
+    let Stmt::Line(retract) = &syntax.stmts[5] else {
+        panic!("expected a line");
+    };
+    assert_eq!(
+        retract.values().collect::<Vec<_>>(),
+        vec!["retract", "[", "v1.0.0", ",", "v1.0.5", "]"]
+    );
+}
+
+#[test]
+fn test_quoted_strings() {
+    let input = indoc! {r#"
+    replace example.com/a => "../my dir/a"
+    replace example.com/b => `C:\path with spaces\b`
+    "#};
+
+    let syntax = FileSyntax::from_str(input).unwrap();
+
+    let values = syntax
+        .lines()
+        .map(|line| line.values().last().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![r#""../my dir/a""#, r"`C:\path with spaces\b`"]);
+}
+
+#[test]
+fn test_unterminated_block() {
+    let input = indoc! {r"
+    module github.com/example
+
+    require (
+        golang.org/x/net v0.20.0
+    "};
+
+    let err = FileSyntax::from_str(input).unwrap_err();
+
+    assert_eq!(err.directive, Some("block".to_string()));
+    assert_eq!(err.expected, vec!["`)`".to_string()]);
+    assert_eq!(err.found, None);
+}
+
+#[test]
+fn test_unterminated_string() {
+    let input = indoc! {r#"
+    replace example.com/a => "../a
+    "#};
+
+    let err = FileSyntax::from_str(input).unwrap_err();
+
+    assert_eq!((err.line, err.column), (1, 31));
+    assert_eq!(err.directive, Some("string".to_string()));
+}
+
+#[test]
+fn test_invalid_block_close() {
+    let input = indoc! {r"
+    require (
+        golang.org/x/net v0.20.0
+    ) golang.org/x/text v0.14.0
+    "};
+
+    let err = FileSyntax::from_str(input).unwrap_err();
+
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(err.directive, Some("block".to_string()));
+    assert_eq!(err.expected, vec!["newline after `)`".to_string()]);
+}
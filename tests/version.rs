@@ -0,0 +1,370 @@
+use gomod_parser::{GoMod, GoVersion, PseudoVersion, Version, VersionInterval};
+use std::cmp::Ordering;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn get_test_file_path(file_name: &str) -> PathBuf {
+    let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.join(format!("tests/data/{file_name}"))
+}
+
+fn get_test_file_content(file_name: &str) -> String {
+    let path = get_test_file_path(file_name);
+    read_to_string(path).unwrap()
+}
+
+fn version(v: &str) -> Version {
+    Version::from_str(v).unwrap()
+}
+
+fn go_version(v: &str) -> GoVersion {
+    GoVersion::from_str(v).unwrap()
+}
+
+#[test]
+fn test_parse_version() {
+    assert_eq!(
+        version("v1.2.3-rc.1+build.5"),
+        Version {
+            major: 1,
+            minor: 2,
+            patch: 3,
+            prerelease: Some("rc.1".to_string()),
+            build: Some("build.5".to_string()),
+        }
+    );
+    assert_eq!(version("v1"), version("v1.0.0"));
+    assert_eq!(version("v1.2").to_string(), "v1.2.0");
+    assert_eq!(
+        version("v0.0.0-20240102150405-abcdef123456").to_string(),
+        "v0.0.0-20240102150405-abcdef123456"
+    );
+    assert!(version("v2.0.0+incompatible").is_incompatible());
+    assert!(!version("v2.0.0").is_incompatible());
+}
+
+#[test]
+fn test_parse_invalid_version() {
+    for invalid in [
+        "",
+        "1.2.3",
+        "v",
+        "v1.",
+        "v01.2.3",
+        "v1.2.03",
+        "v1.2-rc.1",
+        "v1+build",
+        "v1.2.3-",
+        "v1.2.3-01",
+        "v1.2.3-rc..1",
+        "v1.2.3+",
+        "v1.2.3 ",
+        "latest",
+    ] {
+        assert!(Version::from_str(invalid).is_err(), "{invalid}");
+    }
+
+    let err = Version::from_str("v1.2.x").unwrap_err();
+
+    assert_eq!(err.column, 6);
+    assert_eq!(err.directive, Some("version".to_string()));
+    assert_eq!(err.expected, vec!["patch version".to_string()]);
+}
+
+#[test]
+fn test_version_ordering() {
+    // ordered as in the semver specification
+    let ordered = [
+        "v0.0.0-20240102150405-abcdef123456",
+        "v0.9.9",
+        "v1.0.0-alpha",
+        "v1.0.0-alpha.1",
+        "v1.0.0-alpha.beta",
+        "v1.0.0-beta",
+        "v1.0.0-beta.2",
+        "v1.0.0-beta.11",
+        "v1.0.0-rc.1",
+        "v1.0.0",
+        "v1.2.0",
+        "v1.10.0",
+        "v2.0.0+incompatible",
+        "v10.0.0",
+    ];
+
+    for pair in ordered.windows(2) {
+        assert!(version(pair[0]) < version(pair[1]), "{pair:?}");
+    }
+
+    let mut shuffled: Vec<_> = ordered.iter().rev().map(|v| version(v)).collect();
+    shuffled.sort();
+    assert_eq!(
+        shuffled.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ordered.to_vec()
+    );
+}
+
+#[test]
+fn test_build_metadata_does_not_affect_precedence() {
+    assert!(version("v1.0.0+a") > version("v1.0.0-rc.1"));
+    assert!(version("v1.0.0+a") < version("v1.0.1"));
+    assert_ne!(version("v1.0.0+a"), version("v1.0.0"));
+    assert_eq!(
+        version("v2.0.0+incompatible").cmp_precedence(&version("v2.0.0")),
+        Ordering::Equal
+    );
+}
+
+#[test]
+fn test_model_version_accessors() {
+    let gomod = GoMod::from_str(&get_test_file_content("kubernetes.mod")).unwrap();
+
+    for dependency in &gomod.require {
+        assert!(
+            dependency.module.parse_version().is_ok(),
+            "{}",
+            dependency.module
+        );
+    }
+    for replacement in &gomod.replace {
+        assert!(replacement.parse_version().is_ok(), "{replacement}");
+    }
+
+    assert_eq!(
+        VersionInterval::Range("v1.0.0".to_string(), "v1.0.5".to_string())
+            .parse_bounds()
+            .unwrap(),
+        (version("v1.0.0"), version("v1.0.5"))
+    );
+    assert_eq!(
+        VersionInterval::Single("v1.1".to_string())
+            .parse_bounds()
+            .unwrap(),
+        (version("v1.1.0"), version("v1.1.0"))
+    );
+}
+
+#[test]
+fn test_decode_pseudo_versions() {
+    let no_tag = version("v0.0.0-20240102150405-abcdef123456")
+        .decode_pseudo()
+        .unwrap();
+    let after_prerelease = version("v1.2.3-rc.1.0.20240102150405-abcdef123456")
+        .decode_pseudo()
+        .unwrap();
+    let after_release = version("v2.0.1-0.20240102150405-abcdef123456+incompatible")
+        .decode_pseudo()
+        .unwrap();
+
+    let time = UNIX_EPOCH + Duration::from_secs(1_704_207_845);
+    assert_eq!(
+        no_tag,
+        PseudoVersion {
+            base: None,
+            time,
+            revision: "abcdef123456".to_string(),
+        }
+    );
+    assert_eq!(after_prerelease.base, Some(version("v1.2.3-rc.1")));
+    assert_eq!(after_prerelease.time, time);
+    assert_eq!(after_release.base, Some(version("v2.0.0+incompatible")));
+    assert_eq!(
+        version("v2.0.0-20190101000000-abcdefabcdef+incompatible").decode_pseudo(),
+        Some(PseudoVersion {
+            base: None,
+            time: UNIX_EPOCH + Duration::from_secs(1_546_300_800),
+            revision: "abcdefabcdef".to_string(),
+        })
+    );
+
+    for not_pseudo in [
+        "v1.2.3",
+        "v1.2.3-rc.1",
+        "v1.2.0-20240102150405-abcdef123456",
+        "v1.2.0-0.20240102150405-abcdef123456",
+        "v0.0.0-20241302150405-abcdef123456",
+        "v0.0.0-2024010215040-abcdef123456",
+        "v1.2.3-rc.20240102150405-abcdef123456",
+    ] {
+        assert!(!version(not_pseudo).is_pseudo(), "{not_pseudo}");
+    }
+}
+
+#[test]
+fn test_build_pseudo_versions() {
+    let time = UNIX_EPOCH + Duration::from_secs(951_782_400);
+
+    assert_eq!(
+        Version::pseudo(2, None, time, "0123456789abcdef0123456789abcdef01234567"),
+        version("v2.0.0-20000229000000-0123456789ab")
+    );
+    assert_eq!(
+        Version::pseudo(1, Some(&version("v1.4.0-beta")), time, "abcdef123456"),
+        version("v1.4.0-beta.0.20000229000000-abcdef123456")
+    );
+    assert_eq!(
+        Version::pseudo(
+            1,
+            Some(&version("v3.1.9+incompatible")),
+            time,
+            "abcdef123456"
+        ),
+        version("v3.1.10-0.20000229000000-abcdef123456+incompatible")
+    );
+}
+
+#[test]
+fn test_pseudo_versions_roundtrip() {
+    let mut count = 0;
+    for file_name in ["kubernetes.mod", "prometheus.mod"] {
+        let gomod = GoMod::from_str(&get_test_file_content(file_name)).unwrap();
+
+        for dependency in &gomod.require {
+            let version = dependency.module.parse_version().unwrap();
+            let Some(pseudo) = version.decode_pseudo() else {
+                continue;
+            };
+            count += 1;
+
+            assert_eq!(
+                Version::pseudo(
+                    version.major,
+                    pseudo.base.as_ref(),
+                    pseudo.time,
+                    &pseudo.revision
+                ),
+                version
+            );
+        }
+    }
+
+    assert!(count > 10);
+}
+
+#[test]
+fn test_parse_go_version() {
+    assert_eq!(
+        go_version("1.22beta2"),
+        GoVersion {
+            major: 1,
+            minor: Some(22),
+            patch: None,
+            prerelease: Some("beta2".to_string()),
+        }
+    );
+    assert_eq!(go_version("1.21.0").patch, Some(0));
+    assert!(go_version("1.21rc1").is_prerelease());
+    assert!(!go_version("1.21.0").is_prerelease());
+    assert_eq!(go_version("1").minor, None);
+    assert_eq!(go_version("1").to_string(), "1");
+    for v in ["1.21", "1.21.0", "1.21rc1", "1.22beta2", "1.20", "1.9.7"] {
+        assert_eq!(go_version(v).to_string(), v);
+    }
+    assert_eq!(go_version("1.21.3").lang(), go_version("1.21"));
+    assert_eq!(go_version("1.22rc1").lang(), go_version("1.22"));
+}
+
+#[test]
+fn test_parse_invalid_go_version() {
+    for invalid in [
+        "",
+        "1.",
+        "1rc1",
+        "v1.21",
+        "go1.21",
+        "01.21",
+        "1.021",
+        "1.21.0rc1",
+        "1.21RC1",
+        "1.21rc01",
+        "1.21-rc1",
+        "1.21.",
+        "1.21 ",
+    ] {
+        assert!(GoVersion::from_str(invalid).is_err(), "{invalid}");
+    }
+
+    let err = GoVersion::from_str("1.21.x").unwrap_err();
+
+    assert_eq!(err.column, 6);
+    assert_eq!(err.directive, Some("go version".to_string()));
+    assert_eq!(err.expected, vec!["patch version".to_string()]);
+}
+
+#[test]
+fn test_go_version_ordering() {
+    let ordered = [
+        "1.0",
+        "1.9",
+        "1.9.1",
+        "1.10rc1",
+        "1.10",
+        "1.20.5",
+        "1.21",
+        "1.21alpha1",
+        "1.21beta1",
+        "1.21beta2",
+        "1.21rc",
+        "1.21rc1",
+        "1.21rc2",
+        "1.21.0",
+        "1.21.1",
+        "1.21.10",
+        "1.22",
+        "2.0",
+    ];
+
+    for pair in ordered.windows(2) {
+        assert!(go_version(pair[0]) < go_version(pair[1]), "{pair:?}");
+    }
+
+    // like Go, `1` is read as `1.0.0`
+    for v in ["1.0", "1.0.0"] {
+        assert_eq!(
+            go_version("1").cmp_precedence(&go_version(v)),
+            Ordering::Equal,
+            "{v}"
+        );
+    }
+
+    // before Go 1.21, the language version is the first release
+    assert_eq!(
+        go_version("1.20").cmp_precedence(&go_version("1.20.0")),
+        Ordering::Equal
+    );
+    assert_ne!(go_version("1.20"), go_version("1.20.0"));
+    assert!(go_version("1.20") < go_version("1.20.0"));
+}
+
+#[test]
+fn test_go_version_from_toolchain() {
+    for (name, v) in [
+        ("go1.21.1", Some("1.21.1")),
+        ("go1.22.0-custom", Some("1.22.0")),
+        ("go1.21rc2", Some("1.21rc2")),
+        ("go1.20 X:boringcrypto", Some("1.20")),
+        ("gccgo-go1.21.0", Some("1.21.0")),
+        ("1.21.0", None),
+        ("local", None),
+        ("go1.21/x", None),
+        ("golatest", None),
+    ] {
+        assert_eq!(GoVersion::from_toolchain(name), v.map(go_version), "{name}");
+    }
+}
+
+#[test]
+fn test_model_go_versions() {
+    let gomod =
+        GoMod::from_str("module example.com/app\n\ngo 1.21.0\n\ntoolchain go1.22.1\n").unwrap();
+
+    assert_eq!(
+        gomod.parse_go_version().unwrap(),
+        Some(go_version("1.21.0"))
+    );
+    assert_eq!(gomod.toolchain_version(), Some(go_version("1.22.1")));
+    assert!(gomod.toolchain_version() > gomod.parse_go_version().unwrap());
+    assert_eq!(GoMod::default().parse_go_version().unwrap(), None);
+    assert_eq!(GoMod::default().toolchain_version(), None);
+}